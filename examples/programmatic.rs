@@ -9,7 +9,8 @@ use std::fs;
 use std::path::Path;
 use yolo_io::YoloDataQualityReport;
 use yolo_io::{
-    Export, Paths, SourcePaths, Split, YoloProject, YoloProjectConfig, YoloProjectExporter,
+    BackgroundPolicy, Export, ExportLayout, ImageValidation, LinkMode, OverwritePolicy, Paths,
+    RowErrorPolicy, SourcePaths, Split, YoloProject, YoloProjectConfig, YoloProjectExporter,
 };
 
 fn ensure_sample_data() {
@@ -50,6 +51,7 @@ fn main() {
     let source_paths = SourcePaths {
         images: "examples/images".to_string(),
         labels: "examples/labels".to_string(),
+        cache: None,
     };
 
     // Assemble the project configuration from the pieces above.
@@ -65,7 +67,18 @@ fn main() {
                 train: 0.8,
                 validation: 0.2,
                 test: 0.0,
+                ..Default::default()
             },
+            keypoints: None,
+            oriented_bounding_boxes: false,
+            link_mode: LinkMode::Copy,
+            layout: ExportLayout::Directories,
+            row_errors: RowErrorPolicy::Reject,
+            strict_formatting: false,
+            background: BackgroundPolicy::Reject,
+            image_validation: ImageValidation::Off,
+            // Replace the output of earlier runs of this example.
+            overwrite: OverwritePolicy::Clean,
         },
     };
 
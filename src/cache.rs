@@ -0,0 +1,193 @@
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path, sync::Mutex, time::SystemTime};
+
+use crate::{
+    file_utils::FileError,
+    pairing::LabelLoader,
+    types::{
+        BackgroundPolicy, FileMetadata, ImageValidation, Keypoints, PairingResult, PathWithKey,
+        RowErrorPolicy,
+    },
+    YoloFile, YoloFileParseError,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Settings a cache was built with. A cache built with other settings is
+/// discarded.
+pub(crate) struct CacheSettings {
+    label_extensions: Vec<String>,
+    class_map: Vec<(isize, String)>,
+    duplicate_tolerance: f32,
+    keypoints: Option<Keypoints>,
+    oriented_bounding_boxes: bool,
+    row_errors: RowErrorPolicy,
+    background: BackgroundPolicy,
+    image_validation: ImageValidation,
+}
+
+impl CacheSettings {
+    pub(crate) fn new(metadata: &FileMetadata, label_extensions: &[&str]) -> Self {
+        let mut class_map = metadata
+            .classes
+            .iter()
+            .map(|class| (class.id, class.name.clone()))
+            .collect::<Vec<(isize, String)>>();
+        class_map.sort();
+
+        Self {
+            label_extensions: label_extensions.iter().map(|ext| ext.to_string()).collect(),
+            class_map,
+            duplicate_tolerance: metadata.duplicate_tolerance,
+            keypoints: metadata.keypoints.clone(),
+            oriented_bounding_boxes: metadata.oriented_bounding_boxes,
+            row_errors: metadata.row_errors,
+            background: metadata.background,
+            image_validation: metadata.image_validation,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Size and modification time of a file when it was read.
+struct FileStamp {
+    size: u64,
+    modified: SystemTime,
+}
+
+impl FileStamp {
+    fn of(path: impl AsRef<Path>) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+
+        Some(Self {
+            size: metadata.len(),
+            modified: metadata.modified().ok()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    stamp: FileStamp,
+    label: Result<YoloFile, YoloFileParseError>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+/// Contents of the cache file.
+struct CacheFile {
+    settings: Option<CacheSettings>,
+    entries: HashMap<String, CacheEntry>,
+    /// Image and label files the pairing results were computed from.
+    #[serde(default)]
+    files: Vec<(String, Option<FileStamp>)>,
+    #[serde(default)]
+    pairs: Option<Vec<PairingResult>>,
+}
+
+/// Parsed label files and pairing results kept between loads of a
+/// project.
+///
+/// Entries are keyed by label path and reused while the file keeps the
+/// size and modification time it had when it was parsed. The pairing
+/// results are reused as a whole while no image or label file was added,
+/// removed or changed.
+pub(crate) struct LabelCache {
+    settings: CacheSettings,
+    files: Vec<(String, Option<FileStamp>)>,
+    previous: HashMap<String, CacheEntry>,
+    previous_pairs: Option<Vec<PairingResult>>,
+    current: Mutex<HashMap<String, CacheEntry>>,
+}
+
+impl LabelCache {
+    /// Read the cache at `path` for a load of `files`. A missing or
+    /// unreadable cache, or one built with different settings, starts
+    /// out empty.
+    pub(crate) fn open(path: &Path, settings: CacheSettings, files: &[&PathWithKey]) -> Self {
+        let mut files = files
+            .iter()
+            .map(|file| {
+                (
+                    file.path.to_string_lossy().into_owned(),
+                    FileStamp::of(&file.path),
+                )
+            })
+            .collect::<Vec<(String, Option<FileStamp>)>>();
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let cache = fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<CacheFile>(&contents).ok())
+            .filter(|cache| cache.settings.as_ref() == Some(&settings))
+            .unwrap_or_default();
+
+        let previous_pairs = cache.pairs.filter(|_| cache.files == files);
+
+        Self {
+            settings,
+            files,
+            previous: cache.entries,
+            previous_pairs,
+            current: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Pairing results of the previous load, if none of its files changed.
+    pub(crate) fn pairs(&self) -> Option<Vec<PairingResult>> {
+        self.previous_pairs.clone()
+    }
+
+    /// Read a label file with `loader` unless an up-to-date entry exists.
+    pub(crate) fn load(
+        &self,
+        loader: LabelLoader,
+        metadata: &FileMetadata,
+        path: &String,
+    ) -> Result<YoloFile, YoloFileParseError> {
+        let Some(stamp) = FileStamp::of(path) else {
+            return loader(metadata, path);
+        };
+
+        let label = match self.previous.get(path) {
+            Some(entry) if entry.stamp == stamp => entry.label.clone(),
+            _ => loader(metadata, path),
+        };
+
+        if let Ok(mut current) = self.current.lock() {
+            current.insert(
+                path.clone(),
+                CacheEntry {
+                    stamp,
+                    label: label.clone(),
+                },
+            );
+        }
+
+        label
+    }
+
+    /// Write the entries used by this load and its pairing results to
+    /// `path`, dropping files that were not seen.
+    pub(crate) fn save(self, path: &Path, pairs: &[PairingResult]) -> Result<(), FileError> {
+        // Labels are not loaded when the previous pairing is reused.
+        let entries = match self.previous_pairs {
+            Some(_) => self.previous,
+            None => self.current.into_inner().unwrap_or_default(),
+        };
+
+        let cache = CacheFile {
+            settings: Some(self.settings),
+            entries,
+            files: self.files,
+            pairs: Some(pairs.to_vec()),
+        };
+
+        let contents = serde_json::to_string(&cache).map_err(|_| FileError::WriteFile)?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|_| FileError::WriteFile)?;
+        }
+
+        fs::write(path, contents).map_err(|_| FileError::WriteFile)
+    }
+}
@@ -0,0 +1,614 @@
+use hashbrown::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+use crate::{
+    export::{place_file, prepare_root},
+    file_utils::get_file_stem,
+    geometry,
+    types::{
+        BackgroundPolicy, DuplicateImageLabelPair, FileMetadata, ImageLabelPair, PairingError,
+        PairingResult, RowErrorPolicy,
+    },
+    ExportError, LabelSource, UnmappableAnnotation, YoloEntry, YoloFile, YoloKeypoint, YoloProject,
+    YoloProjectExporter,
+};
+
+/// Errors returned when an annotation file cannot be imported.
+
+#[derive(Debug, Error, PartialEq, Clone, Serialize, Deserialize)]
+pub enum ImportError {
+    #[error("Unable to read annotation file '{0}'.")]
+    ReadFile(String),
+    #[error("Unable to parse annotation file '{0}': {1}")]
+    ParseFile(String, String),
+    #[error("Image validation requires the 'image-validation' feature.")]
+    ImageValidationUnavailable,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Top level structure of a COCO `instances.json` file.
+pub struct CocoDataset {
+    /// Images described by the file.
+    pub images: Vec<CocoImage>,
+    /// Object annotations for the images.
+    #[serde(default)]
+    pub annotations: Vec<CocoAnnotation>,
+    /// Object categories referenced by the annotations.
+    pub categories: Vec<CocoCategory>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// An image entry in a COCO file.
+pub struct CocoImage {
+    /// Unique image identifier.
+    pub id: u64,
+    /// File name relative to the image directory.
+    pub file_name: String,
+    /// Image width in pixels.
+    pub width: u32,
+    /// Image height in pixels.
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// An object annotation in a COCO file.
+pub struct CocoAnnotation {
+    /// Unique annotation identifier.
+    pub id: u64,
+    /// Image the annotation belongs to.
+    pub image_id: u64,
+    /// Category of the annotated object.
+    pub category_id: u64,
+    /// Bounding box as `[x, y, width, height]` in pixels.
+    pub bbox: [f32; 4],
+    /// Area of the object in pixels.
+    #[serde(default)]
+    pub area: f32,
+    /// Whether the annotation marks a crowd of objects.
+    #[serde(default)]
+    pub iscrowd: u8,
+    /// Outline of the object.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub segmentation: Option<CocoSegmentation>,
+    /// Keypoints as flat `[x, y, visibility, ...]` pixel triples.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keypoints: Option<Vec<f32>>,
+    /// Number of labelled keypoints.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_keypoints: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+/// Outline of a COCO annotation.
+pub enum CocoSegmentation {
+    /// Polygons as flat `[x, y, x, y, ...]` pixel coordinates.
+    Polygons(Vec<Vec<f32>>),
+    /// Run-length encoded mask, kept as read.
+    Rle(serde_json::Value),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// An object category in a COCO file.
+pub struct CocoCategory {
+    /// Unique category identifier.
+    pub id: u64,
+    /// Human readable category name.
+    pub name: String,
+    /// Optional parent category.
+    #[serde(default)]
+    pub supercategory: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Pairs and classes produced by importing a COCO file.
+pub struct CocoImport {
+    /// File stems of every image in the file.
+    pub stems: Vec<String>,
+    /// Pairing results for every image, followed by unmappable annotations.
+    pub pairs: Vec<PairingResult>,
+    /// Mapping of YOLO class id to class name.
+    pub class_map: HashMap<isize, String>,
+}
+
+/// Converts COCO annotations into YOLO image/label pairs.
+///
+/// COCO categories are sorted by id and numbered from zero to build the
+/// YOLO class map. Pixel bounding boxes are normalized using the image
+/// dimensions recorded in the JSON file. Annotations that cannot be
+/// converted, including crowd annotations (`iscrowd: 1`) and boxes that
+/// duplicate an earlier box of the same image within the duplicate
+/// tolerance, are reported as [`PairingError::UnmappableAnnotation`].
+/// The image they belong to is excluded from the valid pairs, unless the
+/// [`RowErrorPolicy`] is [`RowErrorPolicy::DropRows`] and the image has
+/// other annotations left. Images without annotations are treated
+/// like images with an empty label file: they are kept as background
+/// images unless the [`BackgroundPolicy`] is [`BackgroundPolicy::Reject`],
+/// in which case they are reported as [`PairingError::LabelFileMissing`].
+/// Images are verified according to the [`crate::ImageValidation`] like
+/// those of YOLO projects.
+pub struct CocoImporter;
+
+impl CocoImporter {
+    /// Read a COCO file and pair its images with the converted labels.
+    ///
+    /// Image file names are resolved relative to `images_dir`. The
+    /// classes, keypoints and oriented boxes of `metadata` are not used.
+    pub fn import(
+        annotations_path: impl AsRef<Path>,
+        images_dir: impl AsRef<Path>,
+        metadata: &FileMetadata,
+    ) -> Result<CocoImport, ImportError> {
+        let annotations_path = annotations_path.as_ref();
+        let path_string = annotations_path.to_string_lossy().to_string();
+
+        let data = fs::read_to_string(annotations_path)
+            .map_err(|_| ImportError::ReadFile(path_string.clone()))?;
+        let dataset: CocoDataset = serde_json::from_str(&data)
+            .map_err(|e| ImportError::ParseFile(path_string.clone(), e.to_string()))?;
+
+        Self::convert(&dataset, &path_string, images_dir.as_ref(), metadata)
+    }
+
+    /// Convert an already parsed [`CocoDataset`].
+    ///
+    /// `source` is recorded as the label path of every converted pair.
+    pub fn convert(
+        dataset: &CocoDataset,
+        source: &str,
+        images_dir: &Path,
+        metadata: &FileMetadata,
+    ) -> Result<CocoImport, ImportError> {
+        #[cfg(not(feature = "image-validation"))]
+        if metadata.image_validation != crate::ImageValidation::Off {
+            return Err(ImportError::ImageValidationUnavailable);
+        }
+
+        let tolerance = metadata.duplicate_tolerance;
+
+        let mut categories = dataset.categories.iter().collect::<Vec<&CocoCategory>>();
+        categories.sort_by_key(|category| category.id);
+
+        let class_ids = categories
+            .iter()
+            .enumerate()
+            .map(|(class, category)| (category.id, class as isize))
+            .collect::<HashMap<u64, isize>>();
+
+        let class_map = categories
+            .iter()
+            .enumerate()
+            .map(|(class, category)| (class as isize, category.name.clone()))
+            .collect::<HashMap<isize, String>>();
+
+        let images = dataset
+            .images
+            .iter()
+            .map(|image| (image.id, image))
+            .collect::<HashMap<u64, &CocoImage>>();
+
+        let mut entries_by_image = HashMap::<u64, Vec<(u64, YoloEntry)>>::new();
+        let mut unmappable_images = HashSet::<u64>::new();
+        let mut unmappable = Vec::<PairingResult>::new();
+
+        for annotation in &dataset.annotations {
+            let image = images.get(&annotation.image_id);
+
+            let result = match image {
+                Some(_) if annotation.iscrowd != 0 => Err("crowd annotation".to_string()),
+                Some(image) => match class_ids.get(&annotation.category_id) {
+                    Some(class) => Self::normalize(annotation, image, *class),
+                    None => Err(format!("unknown category id {}", annotation.category_id)),
+                },
+                None => Err(format!("unknown image id {}", annotation.image_id)),
+            };
+
+            let result = result.and_then(|entry| {
+                let duplicate = entries_by_image
+                    .get(&annotation.image_id)
+                    .into_iter()
+                    .flatten()
+                    .find(|(_, previous)| {
+                        tolerance > 0.0 && previous.is_duplicate_of(&entry, tolerance)
+                    });
+
+                match duplicate {
+                    Some((id, _)) => Err(format!("duplicate of annotation {}", id)),
+                    None => Ok(entry),
+                }
+            });
+
+            match result {
+                Ok(entry) => entries_by_image
+                    .entry(annotation.image_id)
+                    .or_default()
+                    .push((annotation.id, entry)),
+                Err(reason) => {
+                    unmappable_images.insert(annotation.image_id);
+                    unmappable.push(PairingResult::Invalid(PairingError::UnmappableAnnotation(
+                        UnmappableAnnotation {
+                            path: source.to_string(),
+                            annotation: Some(annotation.id.to_string()),
+                            image: image.map(|image| image.file_name.clone()),
+                            reason,
+                        },
+                    )));
+                }
+            }
+        }
+
+        let mut sorted_images = dataset.images.iter().collect::<Vec<&CocoImage>>();
+        sorted_images.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+
+        let mut pairs = Vec::<PairingResult>::new();
+        let mut primary_pairs = HashMap::<String, ImageLabelPair>::new();
+
+        for image in sorted_images {
+            let drops_rows = metadata.row_errors == RowErrorPolicy::DropRows
+                && entries_by_image.contains_key(&image.id);
+
+            if unmappable_images.contains(&image.id) && !drops_rows {
+                continue;
+            }
+
+            let image_path = images_dir.join(&image.file_name);
+
+            if !image_path.exists() {
+                pairs.push(PairingResult::Invalid(PairingError::ImageFileMissing(
+                    image_path.to_string_lossy().to_string(),
+                )));
+                continue;
+            }
+
+            let entries = match entries_by_image.remove(&image.id) {
+                Some(entries) => entries.into_iter().map(|(_, entry)| entry).collect(),
+                None if metadata.background != BackgroundPolicy::Reject => vec![],
+                None => {
+                    pairs.push(PairingResult::Invalid(PairingError::LabelFileMissing(
+                        image_path.to_string_lossy().to_string(),
+                    )));
+                    continue;
+                }
+            };
+
+            let stem = match get_file_stem(&image_path) {
+                Ok(stem) => stem.to_string(),
+                Err(_) => {
+                    pairs.push(PairingResult::Invalid(
+                        PairingError::LabelFileMissingUnableToUnwrapImagePath,
+                    ));
+                    continue;
+                }
+            };
+
+            let pair = ImageLabelPair {
+                name: stem.clone(),
+                image_path: Some(PathBuf::from(&image_path)),
+                label_file: Some(YoloFile {
+                    stem: stem.clone(),
+                    path: source.to_string(),
+                    entries,
+                    polygons: vec![],
+                    poses: vec![],
+                    oriented_boxes: vec![],
+                    source: LabelSource::Coco,
+                    warnings: vec![],
+                    formatting: vec![],
+                }),
+            };
+
+            #[cfg(feature = "image-validation")]
+            let pair = match crate::image_check::check_pair(pair, metadata.image_validation) {
+                PairingResult::Valid(pair) => pair,
+                invalid => {
+                    pairs.push(invalid);
+                    continue;
+                }
+            };
+
+            match primary_pairs.get(&stem) {
+                Some(primary) => {
+                    pairs.push(PairingResult::Invalid(PairingError::Duplicate(
+                        DuplicateImageLabelPair {
+                            name: stem,
+                            primary: primary.clone(),
+                            duplicate: pair,
+                        },
+                    )));
+                }
+                None => {
+                    primary_pairs.insert(stem, pair.clone());
+                    pairs.push(PairingResult::Valid(pair));
+                }
+            }
+        }
+
+        pairs.extend(unmappable);
+
+        let mut stems = dataset
+            .images
+            .iter()
+            .filter_map(|image| {
+                get_file_stem(Path::new(&image.file_name))
+                    .ok()
+                    .map(|stem| stem.to_string())
+            })
+            .collect::<Vec<String>>();
+        stems.sort();
+        stems.dedup();
+
+        Ok(CocoImport {
+            stems,
+            pairs,
+            class_map,
+        })
+    }
+
+    fn normalize(
+        annotation: &CocoAnnotation,
+        image: &CocoImage,
+        class: isize,
+    ) -> Result<YoloEntry, String> {
+        if image.width == 0 || image.height == 0 {
+            return Err(String::from("image has no width or height"));
+        }
+
+        let [x, y, width, height] = annotation.bbox;
+
+        if width <= 0.0 || height <= 0.0 {
+            return Err(String::from("bounding box has no area"));
+        }
+
+        let image_width = image.width as f32;
+        let image_height = image.height as f32;
+
+        let inside_image =
+            x >= 0.0 && y >= 0.0 && x + width <= image_width && y + height <= image_height;
+
+        if !inside_image {
+            return Err(String::from("bounding box extends outside the image"));
+        }
+
+        Ok(YoloEntry {
+            class,
+            x_center: (x + width / 2.0) / image_width,
+            y_center: (y + height / 2.0) / image_height,
+            width: width / image_width,
+            height: height / image_height,
+        })
+    }
+}
+
+/// Writes a [`YoloProject`] as COCO `instances_<split>.json` files.
+///
+/// Valid pairs are split the same way as [`YoloProjectExporter::export`]
+/// does. Images are copied into the `images` directory of each split and
+/// one COCO file per split is written to the export root. Labels are
+/// converted to pixel coordinates using the dimensions read from each
+/// image file; COCO category ids are the YOLO class ids plus one.
+///
+/// Every label gets a `bbox`. Polygons and oriented boxes are also
+/// written as `segmentation` polygons and poses as `keypoints`.
+pub struct CocoExporter;
+
+impl CocoExporter {
+    /// Export the project to COCO files according to its configuration.
+    pub fn export(project: YoloProject) -> Result<(), ExportError> {
+        let paths = &project.config.export.paths;
+        let class_map = &project.config.export.class_map;
+
+        let (train_pairs, validation_pairs, test_pairs) = YoloProjectExporter::split_pairs(
+            project.get_valid_pairs(),
+            project.config.export.split.clone(),
+        )?;
+
+        prepare_root(&paths.get_root(), project.config.export.overwrite)?;
+
+        let splits = vec![
+            (
+                paths.get_train_stem(),
+                paths.get_train_images_path(),
+                train_pairs,
+            ),
+            (
+                paths.get_validation_stem(),
+                paths.get_validation_images_path(),
+                validation_pairs,
+            ),
+            (
+                paths.get_test_stem(),
+                paths.get_test_images_path(),
+                test_pairs,
+            ),
+        ];
+
+        for (stem, images_path, pairs) in splits {
+            fs::create_dir_all(&images_path).map_err(|_| {
+                ExportError::UnableToCreateDirectory(images_path.to_string_lossy().into_owned())
+            })?;
+
+            let dataset = Self::to_dataset(&pairs, class_map)?;
+
+            for (image, pair) in dataset.images.iter().zip(pairs.iter()) {
+                let source = pair
+                    .image_path
+                    .as_ref()
+                    .ok_or(ExportError::FailedToUnwrapLabelPath)?;
+                let destination = images_path.join(&image.file_name);
+
+                place_file(source, &destination, project.config.export.link_mode)?;
+            }
+
+            let split_name = stem.trim_end_matches(['/', '\\']);
+            let json_path = paths
+                .get_root()
+                .join(format!("instances_{}.json", split_name));
+
+            let json = serde_json::to_string_pretty(&dataset)
+                .map_err(|_| ExportError::WriteFile(json_path.to_string_lossy().into()))?;
+
+            fs::write(&json_path, json)
+                .map_err(|_| ExportError::WriteFile(json_path.to_string_lossy().into()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Convert image/label pairs into a [`CocoDataset`].
+    ///
+    /// Image ids follow the order of `pairs` starting at one and each image
+    /// keeps the file name `<stem>.<extension>` it is exported under.
+    pub fn to_dataset(
+        pairs: &[ImageLabelPair],
+        class_map: &HashMap<isize, String>,
+    ) -> Result<CocoDataset, ExportError> {
+        let mut classes = class_map
+            .iter()
+            .map(|(id, name)| (*id, name.clone()))
+            .collect::<Vec<(isize, String)>>();
+        classes.sort_by_key(|(id, _)| *id);
+
+        let categories = classes
+            .into_iter()
+            .map(|(id, name)| CocoCategory {
+                id: (id + 1) as u64,
+                name,
+                supercategory: None,
+            })
+            .collect::<Vec<CocoCategory>>();
+
+        let mut images = Vec::<CocoImage>::new();
+        let mut annotations = Vec::<CocoAnnotation>::new();
+
+        for (index, pair) in pairs.iter().enumerate() {
+            let image_path = pair
+                .image_path
+                .as_ref()
+                .ok_or(ExportError::FailedToUnwrapLabelPath)?;
+
+            let size = imagesize::size(image_path).map_err(|_| {
+                ExportError::ReadImageDimensions(image_path.to_string_lossy().into_owned())
+            })?;
+
+            let image_id = (index + 1) as u64;
+            let width = size.width as f32;
+            let height = size.height as f32;
+
+            let extension = image_path
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or("");
+
+            images.push(CocoImage {
+                id: image_id,
+                file_name: PathBuf::from(&pair.name)
+                    .with_extension(extension)
+                    .to_string_lossy()
+                    .into_owned(),
+                width: size.width as u32,
+                height: size.height as u32,
+            });
+
+            let Some(label_file) = pair.label_file.as_ref() else {
+                continue;
+            };
+
+            // Every label as its bounding box with an optional outline
+            // and keypoints.
+            let instances = label_file
+                .entries
+                .iter()
+                .map(|entry| (entry.clone(), None, None))
+                .chain(label_file.polygons.iter().map(|polygon| {
+                    (
+                        YoloEntry::enclosing(polygon.class, &polygon.points),
+                        Some(polygon.points.as_slice()),
+                        None,
+                    )
+                }))
+                .chain(
+                    label_file
+                        .poses
+                        .iter()
+                        .map(|pose| (pose.entry.clone(), None, Some(pose.keypoints.as_slice()))),
+                )
+                .chain(label_file.oriented_boxes.iter().map(|oriented_box| {
+                    (
+                        YoloEntry::enclosing(oriented_box.class, &oriented_box.corners),
+                        Some(oriented_box.corners.as_slice()),
+                        None,
+                    )
+                }))
+                .collect::<Vec<(YoloEntry, Option<&[(f32, f32)]>, Option<&[YoloKeypoint]>)>>();
+
+            for (entry, outline, keypoints) in instances {
+                let box_width = entry.width * width;
+                let box_height = entry.height * height;
+
+                let area = match outline {
+                    Some(points) => geometry::signed_area(points).abs() * width * height,
+                    None => box_width * box_height,
+                };
+
+                let segmentation = outline.map(|points| {
+                    CocoSegmentation::Polygons(vec![points
+                        .iter()
+                        .flat_map(|(x, y)| [x * width, y * height])
+                        .collect()])
+                });
+
+                let keypoints = keypoints.map(|keypoints| {
+                    keypoints
+                        .iter()
+                        .map(|keypoint| {
+                            let visibility = match keypoint.visibility {
+                                Some(visibility) => visibility,
+                                None if keypoint.x == 0.0 && keypoint.y == 0.0 => 0,
+                                None => 2,
+                            };
+                            (keypoint.x * width, keypoint.y * height, visibility)
+                        })
+                        .collect::<Vec<(f32, f32, u8)>>()
+                });
+
+                annotations.push(CocoAnnotation {
+                    id: (annotations.len() + 1) as u64,
+                    image_id,
+                    category_id: (entry.class + 1) as u64,
+                    bbox: [
+                        (entry.x_center - entry.width / 2.0) * width,
+                        (entry.y_center - entry.height / 2.0) * height,
+                        box_width,
+                        box_height,
+                    ],
+                    area,
+                    iscrowd: 0,
+                    segmentation,
+                    num_keypoints: keypoints.as_ref().map(|keypoints| {
+                        keypoints
+                            .iter()
+                            .filter(|(_, _, visibility)| *visibility > 0)
+                            .count()
+                    }),
+                    keypoints: keypoints.map(|keypoints| {
+                        keypoints
+                            .into_iter()
+                            .flat_map(|(x, y, visibility)| [x, y, visibility as f32])
+                            .collect()
+                    }),
+                });
+            }
+        }
+
+        Ok(CocoDataset {
+            images,
+            annotations,
+            categories,
+        })
+    }
+}
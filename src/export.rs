@@ -1,17 +1,19 @@
 use hashbrown::HashMap;
 use log::debug;
-use rand::seq::SliceRandom;
-use rand::thread_rng;
 use std::{
-    fs,
-    path::{Path, PathBuf},
+    fs, io,
+    path::{Component, Path, PathBuf},
 };
 
 use thiserror::Error;
 
 use crate::{
-    types::{ImageLabelPair, Paths, Split},
-    YoloProject,
+    file_utils::absolute_path,
+    plan::{ExportPlan, FileOperation, PlannedFile},
+    report::SplitReport,
+    split::{self, SplitPairs},
+    types::{ExportLayout, ImageLabelPair, Keypoints, LinkMode, OverwritePolicy, Paths, Split},
+    LabelSource, YoloFileParseError, YoloProject,
 };
 
 /// Errors that may occur while exporting a project.
@@ -30,6 +32,30 @@ pub enum ExportError {
     ParseConfig(String),
     #[error("Failed to write file '{0}'")]
     WriteFile(String),
+    #[error("Failed to read the dimensions of image '{0}'")]
+    ReadImageDimensions(String),
+    #[error("Invalid split group pattern: {0}")]
+    InvalidGroupPattern(String),
+    #[error("Unable to create {0} folds from {1} groups of pairs")]
+    InvalidFoldCount(usize, usize),
+    #[error("Failed to link file '{0}' to '{1}': {2}")]
+    FailedToLinkFile(String, String, String),
+    #[error("Failed to move file '{0}' to '{1}'.")]
+    FailedToMoveFile(String, String),
+    #[error("Failed to checksum file '{0}': {1}")]
+    ChecksumFile(String, String),
+    #[error("Export directory '{0}' is not empty")]
+    ExportRootNotEmpty(String),
+    #[error("Unable to clean '{0}' directory")]
+    UnableToCleanDirectory(String),
+    #[error("flip_idx has {0} entries but there are {1} keypoints")]
+    FlipIdxLength(usize, usize),
+    #[error("flip_idx entry {0} is not below the keypoint count {1}")]
+    FlipIdxOutOfRange(usize, usize),
+    #[error("Label '{0}' must be rewritten and cannot be exported as a file list")]
+    FileListLabelRewrite(String),
+    #[error("Label '{0}' is not at '{1}' where YOLO tooling looks for it")]
+    FileListLabelLocation(String, String),
 }
 
 /// Handles writing a [`YoloProject`] to disk.
@@ -48,129 +74,402 @@ impl YoloProjectExporter {
     /// The project is split into training, validation and test sets
     /// based on the configured ratio. A `data.yaml` file is produced
     /// alongside the copied images and labels so the dataset can be
-    /// consumed directly by YOLO tooling.
+    /// consumed directly by YOLO tooling, together with a
+    /// `manifest.json` recording the checksum and origin of every file.
     pub fn export(project: YoloProject) -> Result<(), ExportError> {
-        let paths = &project.config.export.paths;
+        Self::plan(&project)?.execute()
+    }
 
-        paths.create_all_directories()?;
+    /// Describe what [`YoloProjectExporter::export`] would do without
+    /// touching the disk.
+    ///
+    /// The split is computed once and stored in the plan, so executing
+    /// the returned plan writes exactly the reviewed files even when no
+    /// seed is configured.
+    pub fn plan(project: &YoloProject) -> Result<ExportPlan, ExportError> {
+        let (train_pairs, validation_pairs, test_pairs) = Self::split_pairs(
+            project.get_valid_pairs(),
+            project.config.export.split.clone(),
+        )?;
+
+        Self::plan_dataset(
+            project,
+            &project.config.export.paths,
+            train_pairs,
+            validation_pairs,
+            test_pairs,
+        )
+    }
 
-        let project_name = &project.config.project_name;
-        let classes = &project.config.export.class_map;
+    /// Write the project as `folds` cross-validation datasets.
+    ///
+    /// A test set is held out using the `test` ratio of the configured
+    /// [`Split`]; the remaining pairs are divided into `folds` partitions.
+    /// Fold `i` is written to `<root>/fold_<i>` with the same layout as
+    /// [`YoloProjectExporter::export`], using partition `i` for validation,
+    /// the other partitions for training and the shared test set. The
+    /// seed, mode and grouping of the split are respected.
+    ///
+    /// The [`OverwritePolicy`] is applied to `<root>` as a whole before
+    /// any fold is written. A root that holds nothing but the folds of
+    /// this export and their staging directories is treated as an
+    /// interrupted run instead: unless the policy is
+    /// [`OverwritePolicy::Clean`], folds whose manifest matches their plan
+    /// are kept and the remaining folds are exported.
+    pub fn export_k_fold(project: YoloProject, folds: usize) -> Result<(), ExportError> {
+        let plans = Self::plan_k_fold(&project, folds)?;
+
+        let root = project.config.export.paths.get_root();
+        let overwrite = project.config.export.overwrite;
+        let resuming = overwrite != OverwritePolicy::Clean && holds_only_folds(&root, &plans);
+
+        if !resuming {
+            prepare_root(&root, overwrite)?;
+        }
 
-        Self::create_yolo_yaml(project_name, paths, classes)?;
+        for plan in plans {
+            if resuming && plan.is_complete() {
+                continue;
+            }
+            plan.execute()?;
+        }
 
-        let (train_pairs, validation_pairs, test_pairs) =
-            Self::split_pairs(project.get_valid_pairs(), project.config.export.split);
+        Ok(())
+    }
 
-        let test_image_path = paths.get_test_images_path();
-        let test_label_path = paths.get_test_label_images_path();
+    /// Describe what [`YoloProjectExporter::export_k_fold`] would do, one
+    /// plan per fold, without touching the disk.
+    pub fn plan_k_fold(
+        project: &YoloProject,
+        folds: usize,
+    ) -> Result<Vec<ExportPlan>, ExportError> {
+        let (partitions, test_pairs) = split::split_folds(
+            project.get_valid_pairs(),
+            &project.config.export.split,
+            folds,
+        )?;
+
+        (0..folds)
+            .map(|index| {
+                let train_pairs = partitions
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, _)| *other != index)
+                    .flat_map(|(_, partition)| partition.iter().cloned())
+                    .collect::<Vec<ImageLabelPair>>();
+
+                Self::plan_dataset(
+                    project,
+                    &project.config.export.paths.for_fold(index),
+                    train_pairs,
+                    partitions[index].clone(),
+                    test_pairs.clone(),
+                )
+            })
+            .collect()
+    }
 
-        let train_image_path = paths.get_train_images_path();
-        let train_label_path = paths.get_train_label_images_path();
+    fn plan_dataset(
+        project: &YoloProject,
+        paths: &Paths,
+        train_pairs: Vec<ImageLabelPair>,
+        validation_pairs: Vec<ImageLabelPair>,
+        test_pairs: Vec<ImageLabelPair>,
+    ) -> Result<ExportPlan, ExportError> {
+        let export = &project.config.export;
+        let keypoints = export.keypoints.as_ref();
+
+        if let Some(keypoints) = keypoints {
+            keypoints.check_flip_idx()?;
+        }
 
-        let val_image_path = paths.get_validation_images_path();
-        let val_label_path = paths.get_validation_label_images_path();
+        let split_report = SplitReport::new(
+            &train_pairs,
+            &validation_pairs,
+            &test_pairs,
+            &export.class_map,
+        );
 
-        let splits: Vec<(PathBuf, PathBuf, Vec<ImageLabelPair>)> = vec![
-            (test_image_path, test_label_path, test_pairs),
-            (train_image_path, train_label_path, train_pairs),
-            (val_image_path, val_label_path, validation_pairs),
+        let splits = [
+            ("train", train_pairs),
+            ("validation", validation_pairs),
+            ("test", test_pairs),
         ];
 
-        for (images_path, labels_path, pairs) in splits {
-            Self::copy_files(&images_path, &labels_path, pairs)?;
-        }
-
-        Ok(())
+        let mut files = Vec::<PlannedFile>::new();
+
+        let (directories, yaml_splits) = match export.layout {
+            ExportLayout::FileLists => {
+                let list_names = ["train.txt", "val.txt", "test.txt"];
+
+                for ((split, pairs), list_name) in splits.iter().zip(list_names) {
+                    files.push(PlannedFile {
+                        split: split.to_string(),
+                        pair: None,
+                        source: None,
+                        destination: paths.get_root().join(list_name),
+                        operation: FileOperation::Write(Self::file_list_contents(pairs)?),
+                        label_count: pairs.iter().map(Self::label_count).sum(),
+                    });
+                }
+
+                (
+                    vec![paths.get_root()],
+                    list_names.map(|list_name| list_name.to_string()),
+                )
+            }
+            ExportLayout::Directories => {
+                let split_paths = [
+                    (
+                        paths.get_train_images_path(),
+                        paths.get_train_label_images_path(),
+                    ),
+                    (
+                        paths.get_validation_images_path(),
+                        paths.get_validation_label_images_path(),
+                    ),
+                    (
+                        paths.get_test_images_path(),
+                        paths.get_test_label_images_path(),
+                    ),
+                ];
+
+                for ((split, pairs), (images_path, labels_path)) in splits.iter().zip(&split_paths)
+                {
+                    for pair in pairs {
+                        files.extend(Self::plan_pair(
+                            split,
+                            pair,
+                            images_path,
+                            labels_path,
+                            export.link_mode,
+                        )?);
+                    }
+                }
+
+                let mut directories = vec![paths.get_root()];
+                for (images_path, labels_path) in split_paths {
+                    directories.push(images_path);
+                    directories.push(labels_path);
+                }
+
+                (
+                    directories,
+                    [
+                        paths.get_train_stem(),
+                        paths.get_validation_stem(),
+                        paths.get_test_stem(),
+                    ],
+                )
+            }
+        };
+
+        Ok(ExportPlan {
+            root: paths.get_root(),
+            directories,
+            yaml_path: paths
+                .get_root()
+                .join(format!("{}.yaml", project.config.project_name)),
+            yaml: Self::yolo_yaml(paths, yaml_splits, &export.class_map, keypoints),
+            split_report_path: paths.get_root().join("split_report.json"),
+            split_report,
+            manifest_path: paths.get_root().join("manifest.json"),
+            overwrite: export.overwrite,
+            files,
+        })
     }
 
-    fn split_pairs(
+    /// Divide pairs into training, validation and test sets according to
+    /// the configured [`Split`].
+    pub(crate) fn split_pairs(
         pairs: Vec<ImageLabelPair>,
         split: Split,
-    ) -> (
-        Vec<ImageLabelPair>,
-        Vec<ImageLabelPair>,
-        Vec<ImageLabelPair>,
-    ) {
-        let mut rng = thread_rng();
-        let mut pairs = pairs;
-        pairs.shuffle(&mut rng);
-
-        let total = pairs.len();
-
-        let num_test_pairs = (split.test * total as f32).round() as usize;
-        let num_val_pairs = (split.validation * total as f32).round() as usize;
-
-        let test_pairs: Vec<ImageLabelPair> =
-            pairs.drain(0..num_test_pairs.min(pairs.len())).collect();
-        let validation_pairs: Vec<ImageLabelPair> =
-            pairs.drain(0..num_val_pairs.min(pairs.len())).collect();
-        let train_pairs = pairs;
-
-        (train_pairs, validation_pairs, test_pairs)
+    ) -> Result<SplitPairs, ExportError> {
+        split::split_pairs(pairs, &split)
     }
 
-    fn copy_files(
-        export_images_path: &Path,
-        export_labels_path: &Path,
-        pairs: Vec<ImageLabelPair>,
-    ) -> Result<(), ExportError> {
+    /// Absolute image path of every pair, one per line.
+    ///
+    /// The lists point at the original labels, so a label that was
+    /// converted, had rows dropped or holds comments, which YOLO tooling
+    /// cannot parse, cannot be exported this way.
+    fn file_list_contents(pairs: &[ImageLabelPair]) -> Result<String, ExportError> {
+        let mut lines = Vec::<String>::new();
         for pair in pairs {
-            debug!("pair: {:?}", pair);
+            if let Some(label_file) = pair.label_file.as_ref() {
+                let unchanged = match label_file.source {
+                    LabelSource::Yolo => {
+                        label_file.warnings.is_empty()
+                            && !label_file
+                                .formatting
+                                .iter()
+                                .any(|notice| matches!(notice, YoloFileParseError::Comment(_)))
+                    }
+                    LabelSource::Missing => true,
+                    _ => false,
+                };
+                if !unchanged {
+                    return Err(ExportError::FileListLabelRewrite(label_file.path.clone()));
+                }
+            }
 
             let image_path = pair
                 .image_path
+                .as_ref()
                 .ok_or(ExportError::FailedToUnwrapLabelPath)?;
+            let image_path = absolute_path(image_path)
+                .map_err(|_| ExportError::WriteFile(image_path.to_string_lossy().into()))?;
+
+            if let Some(label_file) = pair.label_file.as_ref() {
+                if label_file.source == LabelSource::Yolo {
+                    let expected = Self::file_list_label_path(&image_path);
+                    let label_path = absolute_path(Path::new(&label_file.path))
+                        .map_err(|_| ExportError::WriteFile(label_file.path.clone()))?;
+                    if label_path != expected {
+                        return Err(ExportError::FileListLabelLocation(
+                            label_file.path.clone(),
+                            expected.to_string_lossy().into_owned(),
+                        ));
+                    }
+                }
+            }
+
+            lines.push(image_path.to_string_lossy().into_owned());
+        }
 
-            let label_file = pair
-                .label_file
-                .ok_or(ExportError::FailedToUnwrapLabelPath)?;
-
-            let label_path = PathBuf::from(label_file.path);
-
-            let image_ext = image_path
-                .extension()
-                .and_then(|e| e.to_str())
-                .unwrap_or("");
-
-            let label_ext = label_path
-                .extension()
-                .and_then(|e| e.to_str())
-                .unwrap_or("");
+        let mut contents = lines.join("\n");
+        if !contents.is_empty() {
+            contents.push('\n');
+        }
 
-            let new_image_path =
-                export_images_path.join(PathBuf::from(&pair.name).with_extension(image_ext));
+        Ok(contents)
+    }
 
-            let new_label_path =
-                export_labels_path.join(PathBuf::from(&pair.name).with_extension(label_ext));
+    /// Path at which YOLO tooling looks for the label of `image_path`.
+    ///
+    /// The last `images` directory is replaced with `labels` and the
+    /// extension with `txt`. Images outside an `images` directory keep
+    /// their label next to them.
+    fn file_list_label_path(image_path: &Path) -> PathBuf {
+        let components = image_path.components().collect::<Vec<_>>();
+        let images = components
+            .iter()
+            .take(components.len().saturating_sub(1))
+            .rposition(|component| component.as_os_str() == "images");
 
-            fs::copy(&image_path, &new_image_path).map_err(|_| {
-                ExportError::FailedToCopyFile(
-                    image_path.to_string_lossy().to_string(),
-                    new_image_path.to_string_lossy().to_string(),
-                )
-            })?;
+        let label_path = components
+            .iter()
+            .enumerate()
+            .map(|(index, component)| match images {
+                Some(images) if images == index => Path::new("labels"),
+                _ => Path::new(component.as_os_str()),
+            })
+            .collect::<PathBuf>();
+
+        label_path.with_extension("txt")
+    }
 
-            fs::copy(&label_path, &new_label_path).map_err(|_| {
-                ExportError::FailedToCopyFile(
-                    label_path.to_string_lossy().to_string(),
-                    new_label_path.to_string_lossy().to_string(),
-                )
-            })?;
-        }
+    /// Number of labelled instances in a pair.
+    fn label_count(pair: &ImageLabelPair) -> usize {
+        pair.label_file
+            .as_ref()
+            .map(|label_file| label_file.classes().len())
+            .unwrap_or(0)
+    }
 
-        Ok(())
+    /// Image and label operations for a single pair.
+    fn plan_pair(
+        split: &str,
+        pair: &ImageLabelPair,
+        export_images_path: &Path,
+        export_labels_path: &Path,
+        link_mode: LinkMode,
+    ) -> Result<[PlannedFile; 2], ExportError> {
+        debug!("pair: {:?}", pair);
+
+        let image_path = pair
+            .image_path
+            .clone()
+            .ok_or(ExportError::FailedToUnwrapLabelPath)?;
+
+        let label_file = pair
+            .label_file
+            .as_ref()
+            .ok_or(ExportError::FailedToUnwrapLabelPath)?;
+
+        let label_path = match label_file.source {
+            LabelSource::Missing => None,
+            _ => Some(PathBuf::from(&label_file.path)),
+        };
+
+        let image_ext = image_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("");
+
+        let label_ext = match label_file.source {
+            LabelSource::Yolo => label_path
+                .as_ref()
+                .and_then(|path| path.extension())
+                .and_then(|e| e.to_str())
+                .unwrap_or(""),
+            _ => "txt",
+        };
+
+        let new_image_path =
+            export_images_path.join(PathBuf::from(&pair.name).with_extension(image_ext));
+
+        let new_label_path =
+            export_labels_path.join(PathBuf::from(&pair.name).with_extension(label_ext));
+
+        let label_count = label_file.classes().len();
+
+        // Labels that were converted, had rows dropped, hold comments or
+        // irregular whitespace, or are missing are rewritten from the
+        // parsed rows.
+        let label_operation = match label_file.source {
+            LabelSource::Yolo
+                if label_file.warnings.is_empty() && label_file.formatting.is_empty() =>
+            {
+                FileOperation::Link(link_mode)
+            }
+            _ => FileOperation::Write(label_file.to_yolo_string()),
+        };
+
+        Ok([
+            PlannedFile {
+                split: split.to_string(),
+                pair: Some(pair.name.clone()),
+                source: Some(image_path),
+                destination: new_image_path,
+                operation: FileOperation::Link(link_mode),
+                label_count,
+            },
+            PlannedFile {
+                split: split.to_string(),
+                pair: Some(pair.name.clone()),
+                source: label_path,
+                destination: new_label_path,
+                operation: label_operation,
+                label_count,
+            },
+        ])
     }
 
-    fn create_yolo_yaml(
-        project_name: &str,
+    /// Contents of `<project_name>.yaml`.
+    ///
+    /// `splits` holds the `train`, `val` and `test` entries, either split
+    /// directories or image list files relative to the root.
+    fn yolo_yaml(
         paths: &Paths,
+        splits: [String; 3],
         classes: &HashMap<isize, String>,
-    ) -> Result<(), ExportError> {
+        keypoints: Option<&Keypoints>,
+    ) -> String {
         let mut classes_vec: Vec<(isize, String)> =
             classes.iter().map(|(&k, v)| (k, v.clone())).collect();
 
-        classes_vec.sort_by(|a, b| a.0.cmp(&b.0));
+        classes_vec.sort_by_key(|a| a.0);
 
         let classes_as_yaml = classes_vec
             .iter()
@@ -178,32 +477,210 @@ impl YoloProjectExporter {
             .collect::<Vec<String>>()
             .join("\n");
 
-        let root_path = paths.get_root();
-        let train_path = paths.get_train_stem();
-        let val_path = paths.get_validation_stem();
-        let test_path = paths.get_test_stem();
-
-        let yolo_yaml = format!(
+        let keypoints_as_yaml = match keypoints {
+            Some(keypoints) => {
+                let mut lines = vec![format!(
+                    "kpt_shape: [{}, {}]",
+                    keypoints.count,
+                    keypoints.dimensions()
+                )];
+
+                if let Some(flip_idx) = &keypoints.flip_idx {
+                    let indices = flip_idx
+                        .iter()
+                        .map(|index| index.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                    lines.push(format!("flip_idx: [{}]", indices));
+                }
+
+                format!("{}\n\n", lines.join("\n"))
+            }
+            None => String::new(),
+        };
+
+        let [train_path, val_path, test_path] = splits;
+
+        format!(
             "# Generate by yolo_io - https://github.com/Ladvien/yolo_io
 path: {}
 train: {}
 val: {}
 test: {}
 
-names:
+{}names:
 {}
 ",
-            root_path.to_string_lossy(),
+            paths.get_root().to_string_lossy(),
             train_path,
             val_path,
             test_path,
+            keypoints_as_yaml,
             classes_as_yaml
-        );
+        )
+    }
+}
 
-        let yolo_yaml_path = root_path.join(format!("{project_name}.yaml"));
-        fs::write(&yolo_yaml_path, yolo_yaml)
-            .map_err(|_| ExportError::WriteFile(yolo_yaml_path.to_string_lossy().into()))?;
+/// Whether `root` is missing or an empty directory.
+pub(crate) fn is_empty_root(root: &Path) -> bool {
+    match fs::read_dir(root) {
+        Ok(mut entries) => entries.next().is_none(),
+        Err(_) => !root.exists(),
+    }
+}
 
-        Ok(())
+/// Whether every entry of `root` is the root or staging directory of one
+/// of `plans`.
+fn holds_only_folds(root: &Path, plans: &[ExportPlan]) -> bool {
+    let fold_names = plans
+        .iter()
+        .flat_map(|plan| [plan.root.clone(), plan.staging_path()])
+        .filter_map(|path| path.file_name().map(|name| name.to_os_string()))
+        .collect::<Vec<_>>();
+
+    match fs::read_dir(root) {
+        Ok(entries) => entries
+            .flatten()
+            .all(|entry| fold_names.contains(&entry.file_name())),
+        Err(_) => !root.exists(),
+    }
+}
+
+/// Apply `overwrite` to an export root before anything is written to it.
+///
+/// Fails when the root holds files and the policy is
+/// [`OverwritePolicy::FailIfNotEmpty`] and removes the root when it is
+/// [`OverwritePolicy::Clean`].
+pub(crate) fn prepare_root(root: &Path, overwrite: OverwritePolicy) -> Result<(), ExportError> {
+    if is_empty_root(root) {
+        return Ok(());
+    }
+
+    match overwrite {
+        OverwritePolicy::FailIfNotEmpty => Err(ExportError::ExportRootNotEmpty(
+            root.to_string_lossy().into_owned(),
+        )),
+        OverwritePolicy::Clean => fs::remove_dir_all(root)
+            .map_err(|_| ExportError::UnableToCleanDirectory(root.to_string_lossy().into_owned())),
+        OverwritePolicy::Merge => Ok(()),
+    }
+}
+
+/// Place `source` at `destination` according to `link_mode`.
+///
+/// An existing file or link at `destination` is removed before the new
+/// file is placed. Hard links fall back to
+/// copying when the two paths are on different file systems.
+pub(crate) fn place_file(
+    source: &Path,
+    destination: &Path,
+    link_mode: LinkMode,
+) -> Result<(), ExportError> {
+    let copy_error = || {
+        ExportError::FailedToCopyFile(
+            source.to_string_lossy().to_string(),
+            destination.to_string_lossy().to_string(),
+        )
+    };
+    let copy = || {
+        fs::copy(source, destination)
+            .map(|_| ())
+            .map_err(|_| copy_error())
+    };
+    let link_error = |error: io::Error| {
+        ExportError::FailedToLinkFile(
+            source.to_string_lossy().to_string(),
+            destination.to_string_lossy().to_string(),
+            error.to_string(),
+        )
+    };
+
+    // Copying onto a link left by an earlier export would write through
+    // it into the linked file, so the destination is always removed.
+    if fs::symlink_metadata(destination).is_ok() {
+        fs::remove_file(destination).map_err(|error| match link_mode {
+            LinkMode::Copy => copy_error(),
+            _ => link_error(error),
+        })?;
+    }
+
+    match link_mode {
+        LinkMode::Copy => copy(),
+        LinkMode::Hardlink => match fs::hard_link(source, destination) {
+            Ok(()) => Ok(()),
+            Err(error) if is_cross_device(&error) => copy(),
+            Err(error) => Err(link_error(error)),
+        },
+        LinkMode::AbsoluteSymlink => {
+            let target = fs::canonicalize(source).map_err(link_error)?;
+            symlink_file(&target, destination).map_err(link_error)
+        }
+        LinkMode::RelativeSymlink => {
+            let target = fs::canonicalize(source).map_err(link_error)?;
+            let link_dir = destination
+                .parent()
+                .map(fs::canonicalize)
+                .unwrap_or_else(std::env::current_dir)
+                .map_err(link_error)?;
+            symlink_file(&relative_path(&link_dir, &target), destination).map_err(link_error)
+        }
     }
 }
+
+/// Path of `target` relative to the directory `from`; both must be absolute.
+fn relative_path(from: &Path, target: &Path) -> PathBuf {
+    let from = from.components().collect::<Vec<Component>>();
+    let target = target.components().collect::<Vec<Component>>();
+
+    let common = from
+        .iter()
+        .zip(target.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut relative = PathBuf::new();
+    for _ in common..from.len() {
+        relative.push("..");
+    }
+    for component in &target[common..] {
+        relative.push(component);
+    }
+
+    relative
+}
+
+/// Whether `error` reports a link between two file systems.
+#[cfg(unix)]
+fn is_cross_device(error: &io::Error) -> bool {
+    // EXDEV
+    error.raw_os_error() == Some(18)
+}
+
+#[cfg(windows)]
+fn is_cross_device(error: &io::Error) -> bool {
+    // ERROR_NOT_SAME_DEVICE
+    error.raw_os_error() == Some(17)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn is_cross_device(_error: &io::Error) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn symlink_file(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink_file(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(target, link)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn symlink_file(_target: &Path, _link: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "symbolic links are not supported on this platform",
+    ))
+}
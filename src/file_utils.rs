@@ -1,6 +1,11 @@
-use std::path::Path;
+use std::{
+    fs::File,
+    io,
+    path::{Path, PathBuf},
+};
 
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 
 use crate::types::PathWithKey;
@@ -17,6 +22,8 @@ pub enum FileError {
     ReadFile(String),
     #[error("Unable to write file.")]
     WriteFile,
+    #[error("Image validation requires the 'image-validation' feature.")]
+    ImageValidationUnavailable,
 }
 
 /// Return the file stem for a path.
@@ -28,6 +35,29 @@ pub fn get_file_stem(file_path: &Path) -> Result<&str, FileError> {
         .ok_or_else(|| FileError::ConvertPathToString(file_path.display().to_string()))
 }
 
+/// SHA-256 digest of a file's contents as a lowercase hex string.
+pub fn sha256_file(file_path: &Path) -> Result<String, FileError> {
+    let mut file = File::open(file_path).map_err(|err| FileError::ReadFile(err.to_string()))?;
+
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher).map_err(|err| FileError::ReadFile(err.to_string()))?;
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// `path` joined onto the current directory unless it is already absolute.
+pub fn absolute_path(path: &Path) -> io::Result<PathBuf> {
+    if path.is_absolute() {
+        Ok(path.to_path_buf())
+    } else {
+        Ok(std::env::current_dir()?.join(path))
+    }
+}
+
 fn get_file_extension(file_path: &Path) -> Result<&str, FileError> {
     let file_extension = file_path
         .extension()
@@ -50,6 +80,9 @@ fn get_filepath_as_string(file_path: &Path) -> Result<String, FileError> {
 }
 
 /// Recursively gather all files under `path` with the given extensions.
+///
+/// With the `parallel` feature subdirectories are scanned concurrently.
+/// The result is sorted by path either way.
 pub fn get_filepaths_for_extension(
     path: &str,
     extensions: Vec<&str>,
@@ -57,6 +90,7 @@ pub fn get_filepaths_for_extension(
     let file_paths = std::fs::read_dir(path).map_err(|err| FileError::ReadFile(err.to_string()))?;
 
     let mut paths = Vec::<PathWithKey>::new();
+    let mut directories = Vec::<String>::new();
     let extensions_lower: Vec<String> = extensions.iter().map(|ext| ext.to_lowercase()).collect();
 
     for file_path in file_paths {
@@ -65,9 +99,7 @@ pub fn get_filepaths_for_extension(
             .path();
 
         if file_path.is_dir() {
-            let file_path = get_filepath_as_string(&file_path)?;
-            let filepaths = get_filepaths_for_extension(&file_path, extensions.clone())?;
-            paths.extend(filepaths);
+            directories.push(get_filepath_as_string(&file_path)?);
 
             // Skip trying to get extension and stem for directories
             continue;
@@ -91,8 +123,36 @@ pub fn get_filepaths_for_extension(
         }
     }
 
+    for filepaths in scan_directories(&directories, &extensions)? {
+        paths.extend(filepaths);
+    }
+
     // Ensure deterministic ordering of returned paths
     paths.sort_by(|a, b| a.path.cmp(&b.path));
 
     Ok(paths)
 }
+
+#[cfg(feature = "parallel")]
+fn scan_directories(
+    directories: &[String],
+    extensions: &[&str],
+) -> Result<Vec<Vec<PathWithKey>>, FileError> {
+    use rayon::prelude::*;
+
+    directories
+        .par_iter()
+        .map(|directory| get_filepaths_for_extension(directory, extensions.to_vec()))
+        .collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn scan_directories(
+    directories: &[String],
+    extensions: &[&str],
+) -> Result<Vec<Vec<PathWithKey>>, FileError> {
+    directories
+        .iter()
+        .map(|directory| get_filepaths_for_extension(directory, extensions.to_vec()))
+        .collect()
+}
@@ -0,0 +1,115 @@
+//! Planar geometry helpers used when validating oriented bounding boxes.
+
+/// Signed area of a polygon; positive when the vertices are counter-clockwise.
+pub fn signed_area(points: &[(f32, f32)]) -> f32 {
+    let mut area = 0.0;
+
+    for (index, (x1, y1)) in points.iter().enumerate() {
+        let (x2, y2) = points[(index + 1) % points.len()];
+        area += x1 * y2 - x2 * y1;
+    }
+
+    area / 2.0
+}
+
+/// Cross product of the edges meeting at every vertex of a polygon.
+pub fn turn_directions(points: &[(f32, f32)]) -> Vec<f32> {
+    (0..points.len())
+        .map(|index| {
+            let (ax, ay) = points[index];
+            let (bx, by) = points[(index + 1) % points.len()];
+            let (cx, cy) = points[(index + 2) % points.len()];
+            (bx - ax) * (cy - by) - (by - ay) * (cx - bx)
+        })
+        .collect()
+}
+
+/// Area of the intersection of two convex polygons.
+///
+/// Both polygons are clipped counter-clockwise using the
+/// Sutherland-Hodgman algorithm, so either winding is accepted.
+pub fn convex_intersection_area(subject: &[(f32, f32)], clip: &[(f32, f32)]) -> f32 {
+    let subject = counter_clockwise(subject);
+    let clip = counter_clockwise(clip);
+
+    let mut output = subject;
+
+    for (index, edge_start) in clip.iter().enumerate() {
+        if output.is_empty() {
+            break;
+        }
+
+        let edge_end = clip[(index + 1) % clip.len()];
+        let input = output;
+        output = Vec::new();
+
+        for (point_index, current) in input.iter().enumerate() {
+            let previous = input[(point_index + input.len() - 1) % input.len()];
+
+            let current_inside = is_left_of(*edge_start, edge_end, *current);
+            let previous_inside = is_left_of(*edge_start, edge_end, previous);
+
+            if current_inside {
+                if !previous_inside {
+                    output.push(intersection(previous, *current, *edge_start, edge_end));
+                }
+                output.push(*current);
+            } else if previous_inside {
+                output.push(intersection(previous, *current, *edge_start, edge_end));
+            }
+        }
+    }
+
+    if output.len() < 3 {
+        return 0.0;
+    }
+
+    signed_area(&output).abs()
+}
+
+/// Intersection over union of two convex polygons.
+pub fn convex_iou(a: &[(f32, f32)], b: &[(f32, f32)]) -> f32 {
+    let intersection = convex_intersection_area(a, b);
+    let union = signed_area(a).abs() + signed_area(b).abs() - intersection;
+
+    if union <= 0.0 {
+        return 0.0;
+    }
+
+    intersection / union
+}
+
+fn counter_clockwise(points: &[(f32, f32)]) -> Vec<(f32, f32)> {
+    let mut points = points.to_vec();
+
+    if signed_area(&points) < 0.0 {
+        points.reverse();
+    }
+
+    points
+}
+
+fn is_left_of(start: (f32, f32), end: (f32, f32), point: (f32, f32)) -> bool {
+    (end.0 - start.0) * (point.1 - start.1) - (end.1 - start.1) * (point.0 - start.0) >= 0.0
+}
+
+fn intersection(p1: (f32, f32), p2: (f32, f32), q1: (f32, f32), q2: (f32, f32)) -> (f32, f32) {
+    let a1 = p2.1 - p1.1;
+    let b1 = p1.0 - p2.0;
+    let c1 = a1 * p1.0 + b1 * p1.1;
+
+    let a2 = q2.1 - q1.1;
+    let b2 = q1.0 - q2.0;
+    let c2 = a2 * q1.0 + b2 * q1.1;
+
+    let determinant = a1 * b2 - a2 * b1;
+
+    if determinant.abs() < f32::EPSILON {
+        return p2;
+    }
+
+    (
+        (b2 * c1 - b1 * c2) / determinant,
+        (a1 * c2 - a2 * c1) / determinant,
+    )
+}
@@ -0,0 +1,126 @@
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use imagesize::ImageType;
+
+use crate::types::{ImageLabelPair, ImageValidation, PairingError, PairingResult};
+
+/// Bytes at the end of a file searched for the end marker of its format.
+///
+/// Some encoders pad files after the marker, so it is not required to be
+/// the very last bytes.
+const TAIL_LENGTH: u64 = 32;
+
+/// JPEG end-of-image marker.
+const JPEG_END: &[u8] = &[0xFF, 0xD9];
+
+/// Type of the final chunk of a PNG file.
+const PNG_END: &[u8] = b"IEND";
+
+/// Problem found with an image file.
+enum ImageDefect {
+    Unreadable,
+    Truncated,
+    /// The file is in the named format, which does not match its extension.
+    Mismatch(String),
+}
+
+/// Verify the image of a valid pair.
+///
+/// Returns the pair unchanged if its image passes the checks selected by
+/// `validation`.
+pub(crate) fn check_pair(pair: ImageLabelPair, validation: ImageValidation) -> PairingResult {
+    let Some(path) = pair.image_path.as_ref() else {
+        return PairingResult::Valid(pair);
+    };
+
+    let result = match validation {
+        ImageValidation::Off => Ok(()),
+        ImageValidation::Header => check_header(path),
+        ImageValidation::Decode => check_header(path).and_then(|_| check_decode(path)),
+    };
+
+    let name = path.to_string_lossy().into_owned();
+
+    match result {
+        Ok(()) => PairingResult::Valid(pair),
+        Err(ImageDefect::Unreadable) => PairingResult::Invalid(PairingError::UnreadableImage(name)),
+        Err(ImageDefect::Truncated) => PairingResult::Invalid(PairingError::TruncatedImage(name)),
+        Err(ImageDefect::Mismatch(format)) => {
+            PairingResult::Invalid(PairingError::ImageExtensionMismatch(name, format))
+        }
+    }
+}
+
+/// Check the format, dimensions and end marker of an image.
+fn check_header(path: &Path) -> Result<(), ImageDefect> {
+    let mut file = File::open(path).map_err(|_| ImageDefect::Unreadable)?;
+
+    let mut header = Vec::<u8>::new();
+    file.by_ref()
+        .take(64)
+        .read_to_end(&mut header)
+        .map_err(|_| ImageDefect::Unreadable)?;
+
+    let image_type = imagesize::image_type(&header).map_err(|_| ImageDefect::Unreadable)?;
+
+    let (format, extensions, end_marker) = match image_type {
+        ImageType::Jpeg => ("JPEG", ["jpg", "jpeg"].as_slice(), JPEG_END),
+        ImageType::Png => ("PNG", ["png"].as_slice(), PNG_END),
+        other => return Err(ImageDefect::Mismatch(format!("{:?}", other))),
+    };
+
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    if !extensions.contains(&extension.as_str()) {
+        return Err(ImageDefect::Mismatch(format.to_string()));
+    }
+
+    let size = imagesize::size(path).map_err(|_| ImageDefect::Unreadable)?;
+    if size.width == 0 || size.height == 0 {
+        return Err(ImageDefect::Unreadable);
+    }
+
+    let length = file
+        .seek(SeekFrom::End(0))
+        .map_err(|_| ImageDefect::Unreadable)?;
+    file.seek(SeekFrom::Start(length.saturating_sub(TAIL_LENGTH)))
+        .map_err(|_| ImageDefect::Unreadable)?;
+
+    let mut tail = Vec::<u8>::new();
+    file.read_to_end(&mut tail)
+        .map_err(|_| ImageDefect::Unreadable)?;
+
+    if !tail
+        .windows(end_marker.len())
+        .any(|window| window == end_marker)
+    {
+        return Err(ImageDefect::Truncated);
+    }
+
+    Ok(())
+}
+
+/// Decode the complete image.
+fn check_decode(path: &Path) -> Result<(), ImageDefect> {
+    image::ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|_| ImageDefect::Unreadable)?
+        .decode()
+        .map_err(|error| match error {
+            image::ImageError::IoError(ref io_error)
+                if io_error.kind() == std::io::ErrorKind::UnexpectedEof =>
+            {
+                ImageDefect::Truncated
+            }
+            _ => ImageDefect::Unreadable,
+        })?;
+
+    Ok(())
+}
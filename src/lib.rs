@@ -16,27 +16,52 @@
 //!     Ok(())
 //! }
 //! ```
+mod cache;
+mod coco;
 mod export;
 mod file_utils;
+mod geometry;
+#[cfg(feature = "image-validation")]
+mod image_check;
+mod manifest;
 mod pairing;
+mod plan;
 mod report;
+mod split;
 mod types;
+mod voc;
 mod yolo_file;
 
+use cache::{CacheSettings, LabelCache};
+pub use coco::{
+    CocoAnnotation, CocoCategory, CocoDataset, CocoExporter, CocoImage, CocoImport, CocoImporter,
+    CocoSegmentation, ImportError,
+};
 pub use export::*;
 use file_utils::get_filepaths_for_extension;
 use file_utils::FileError;
-use pairing::pair;
+use log::warn;
+pub use manifest::{Manifest, ManifestEntry};
+use pairing::{pair, LabelLoader};
+pub use plan::{ExportPlan, FileOperation, PlannedFile};
 pub use report::generate_yaml;
 pub use report::DataQualityItem;
 pub use report::YoloDataQualityReport;
+pub use report::{ClassDistribution, SplitReport};
 pub use types::{
-    DuplicateImageLabelPair, Export, FileMetadata, ImageLabelPair, PairingError, PairingResult,
-    PathWithKey, Paths, SourcePaths, Split, YoloClass, YoloProjectConfig,
+    BackgroundPolicy, DuplicateImageLabelPair, Export, ExportLayout, FileMetadata, ImageLabelPair,
+    ImageValidation, Keypoints, LinkMode, OverwritePolicy, PairingError, PairingResult,
+    PathWithKey, Paths, RowErrorPolicy, SourcePaths, Split, SplitGroup, SplitMode,
+    UnmappableAnnotation, YoloClass, YoloProjectConfig,
+};
+pub use voc::{VocAnnotation, VocBndBox, VocExporter, VocObject, VocSize};
+pub use yolo_file::{
+    LabelSource, YoloEntry, YoloFile, YoloFileParseError, YoloFileParseErrorDetails, YoloKeypoint,
+    YoloOrientedBox, YoloPolygon, YoloPose,
 };
-pub use yolo_file::{YoloEntry, YoloFile, YoloFileParseError, YoloFileParseErrorDetails};
 
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// Results of scanning the source directories when a project is loaded.
@@ -89,12 +114,36 @@ impl YoloProject {
     /// results of this process are stored within the returned
     /// [`YoloProject`] for further inspection or export.
     pub fn new(config: &YoloProjectConfig) -> Result<Self, FileError> {
+        Self::load(config, vec!["txt"], YoloFile::new)
+    }
+
+    /// Load a project whose labels are Pascal VOC XML files.
+    ///
+    /// XML files are read from `config.source_paths.labels` and paired
+    /// with images by file stem, exactly like YOLO label files. Boxes are
+    /// normalized with the `<size>` of each annotation and `<name>` is
+    /// looked up in the configured class map.
+    pub fn from_voc(config: &YoloProjectConfig) -> Result<Self, FileError> {
+        Self::load(config, vec!["xml"], YoloFile::from_voc)
+    }
+
+    fn load(
+        config: &YoloProjectConfig,
+        label_extensions: Vec<&str>,
+        loader: LabelLoader,
+    ) -> Result<Self, FileError> {
+        #[cfg(not(feature = "image-validation"))]
+        if config.export.image_validation != types::ImageValidation::Off {
+            return Err(FileError::ImageValidationUnavailable);
+        }
+
         let image_paths = get_filepaths_for_extension(
             &config.source_paths.images,
             vec!["jpg", "png", "PNG", "JPEG"],
         )?;
 
-        let label_paths = get_filepaths_for_extension(&config.source_paths.labels, vec!["txt"])?;
+        let label_paths =
+            get_filepaths_for_extension(&config.source_paths.labels, label_extensions.clone())?;
 
         let all_filepaths = image_paths
             .iter()
@@ -120,9 +169,48 @@ impl YoloProject {
         let metadata = FileMetadata {
             classes: classes.clone(),
             duplicate_tolerance: config.export.duplicate_tolerance,
+            keypoints: config.export.keypoints.clone(),
+            oriented_bounding_boxes: config.export.oriented_bounding_boxes,
+            row_errors: config.export.row_errors,
+            background: config.export.background,
+            image_validation: config.export.image_validation,
+        };
+
+        let cache = config.source_paths.cache.as_ref().map(|cache_path| {
+            LabelCache::open(
+                cache_path,
+                CacheSettings::new(&metadata, &label_extensions),
+                &all_filepaths,
+            )
+        });
+
+        let load_label = |metadata: &FileMetadata, path: &String| match &cache {
+            Some(cache) => cache.load(loader, metadata, path),
+            None => loader(metadata, path),
+        };
+
+        let pairs = match cache.as_ref().and_then(LabelCache::pairs) {
+            Some(pairs) => pairs,
+            None => pair(
+                metadata,
+                stems.clone(),
+                label_paths,
+                image_paths,
+                &load_label,
+            ),
         };
 
-        let pairs = pair(metadata, stems.clone(), label_paths, image_paths);
+        if let (Some(cache), Some(cache_path)) = (cache, &config.source_paths.cache) {
+            // The cache only speeds up later loads; failing to write it
+            // does not fail this one.
+            if let Err(error) = cache.save(cache_path, &pairs) {
+                warn!(
+                    "Unable to write cache '{}': {}",
+                    cache_path.to_string_lossy(),
+                    error
+                );
+            }
+        }
 
         Ok(Self {
             data: YoloProjectData {
@@ -134,6 +222,43 @@ impl YoloProject {
         })
     }
 
+    /// Load a project from a COCO `instances.json` file.
+    ///
+    /// Image file names in the COCO file are resolved relative to
+    /// `config.source_paths.images`. The class map of the returned
+    /// project is replaced with the COCO categories, numbered from zero
+    /// in order of their COCO id. Images without annotations, invalid and
+    /// duplicate annotations and image files are handled according to
+    /// the background, row error, duplicate tolerance and image
+    /// validation settings of `config.export`.
+    pub fn from_coco(
+        config: &YoloProjectConfig,
+        annotations_path: impl AsRef<Path>,
+    ) -> Result<Self, ImportError> {
+        let metadata = FileMetadata {
+            duplicate_tolerance: config.export.duplicate_tolerance,
+            row_errors: config.export.row_errors,
+            background: config.export.background,
+            image_validation: config.export.image_validation,
+            ..Default::default()
+        };
+
+        let import =
+            CocoImporter::import(annotations_path, &config.source_paths.images, &metadata)?;
+
+        let mut config = config.clone();
+        config.export.class_map = import.class_map;
+
+        Ok(Self {
+            data: YoloProjectData {
+                stems: import.stems,
+                pairs: import.pairs,
+                number_of_classes: config.export.class_map.len(),
+            },
+            config,
+        })
+    }
+
     /// Retrieve all successfully paired image/label combinations.
     pub fn get_valid_pairs(&self) -> Vec<ImageLabelPair> {
         self.data
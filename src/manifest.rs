@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{file_utils::sha256_file, plan::PlannedFile, ExportError};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Contents of the `manifest.json` written to the root of every export.
+pub struct Manifest {
+    /// Every file placed in or written to the export directory.
+    pub files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// A single exported file and where it came from.
+pub struct ManifestEntry {
+    /// Split the file belongs to: `train`, `validation` or `test`. Empty
+    /// for the dataset YAML file and the split report.
+    pub split: String,
+    /// Stem of the image/label pair the file belongs to, if any.
+    pub pair: Option<String>,
+    /// File the exported file was created from, if any.
+    pub source: Option<PathBuf>,
+    /// Path of the exported file.
+    pub destination: PathBuf,
+    /// SHA-256 digest of the exported contents as a lowercase hex string.
+    pub sha256: String,
+    /// Size of the exported file in bytes.
+    pub size: u64,
+    /// Number of labelled instances in the pair or image list.
+    pub label_count: usize,
+}
+
+impl Manifest {
+    /// Describe the dataset files in `described` and the planned files,
+    /// reading each one from the path returned by `locate` for its
+    /// destination.
+    pub(crate) fn new(
+        described: &[&Path],
+        files: &[PlannedFile],
+        locate: impl Fn(&Path) -> PathBuf,
+    ) -> Result<Self, ExportError> {
+        let mut entries = Vec::<ManifestEntry>::new();
+
+        for destination in described {
+            let (size, sha256) = checksum(&locate(destination))?;
+
+            entries.push(ManifestEntry {
+                split: String::new(),
+                pair: None,
+                source: None,
+                destination: destination.to_path_buf(),
+                sha256,
+                size,
+                label_count: 0,
+            });
+        }
+
+        for file in files {
+            let (size, sha256) = checksum(&locate(&file.destination))?;
+
+            entries.push(ManifestEntry {
+                split: file.split.clone(),
+                pair: file.pair.clone(),
+                source: file.source.clone(),
+                destination: file.destination.clone(),
+                sha256,
+                size,
+                label_count: file.label_count,
+            });
+        }
+
+        Ok(Self { files: entries })
+    }
+}
+
+/// Size and SHA-256 digest of a written file.
+fn checksum(written: &Path) -> Result<(u64, String), ExportError> {
+    let checksum_error =
+        |err: String| ExportError::ChecksumFile(written.to_string_lossy().into_owned(), err);
+
+    let size = fs::metadata(written)
+        .map_err(|err| checksum_error(err.to_string()))?
+        .len();
+    let sha256 = sha256_file(written).map_err(|err| checksum_error(err.to_string()))?;
+
+    Ok((size, sha256))
+}
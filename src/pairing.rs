@@ -1,110 +1,188 @@
+use hashbrown::HashMap;
 use itertools::{EitherOrBoth, Itertools};
 use std::path::PathBuf;
 
 use crate::types::{
-    DuplicateImageLabelPair, FileMetadata, ImageLabelPair, PairingError, PairingResult, PathWithKey,
+    BackgroundPolicy, DuplicateImageLabelPair, FileMetadata, ImageLabelPair, PairingError,
+    PairingResult, PathWithKey,
 };
-use crate::YoloFile;
+use crate::{YoloFile, YoloFileParseError};
+
+/// Function used to read and validate a label file.
+pub type LabelLoader = fn(&FileMetadata, &String) -> Result<YoloFile, YoloFileParseError>;
 
 /// Pair images and labels based on matching file stems.
-pub fn pair(
+///
+/// Files are indexed by stem once, so pairing takes time linear in the
+/// number of files. Label files are read and validated with `loader`.
+/// With the `parallel` feature stems are paired concurrently; results
+/// keep the order of `stems` either way.
+pub fn pair<L>(
     file_metadata: FileMetadata,
     stems: Vec<String>,
     label_filenames: Vec<PathWithKey>,
     image_filenames: Vec<PathWithKey>,
-) -> Vec<PairingResult> {
+    loader: &L,
+) -> Vec<PairingResult>
+where
+    L: Fn(&FileMetadata, &String) -> Result<YoloFile, YoloFileParseError> + Sync,
+{
+    let images_by_stem = index_by_stem(&image_filenames);
+    let labels_by_stem = index_by_stem(&label_filenames);
+
+    let pair_one = |stem: String| {
+        let image_paths = images_by_stem.get(stem.as_str()).map(Vec::as_slice);
+        let label_paths = labels_by_stem.get(stem.as_str()).map(Vec::as_slice);
+
+        pair_stem(
+            &file_metadata,
+            stem,
+            label_paths.unwrap_or_default(),
+            image_paths.unwrap_or_default(),
+            loader,
+        )
+    };
+
+    #[cfg(feature = "parallel")]
+    let pairs_per_stem = {
+        use rayon::prelude::*;
+        stems
+            .into_par_iter()
+            .map(pair_one)
+            .collect::<Vec<Vec<PairingResult>>>()
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    let pairs_per_stem = stems
+        .into_iter()
+        .map(pair_one)
+        .collect::<Vec<Vec<PairingResult>>>();
+
+    pairs_per_stem.into_iter().flatten().collect()
+}
+
+/// Group file paths by their stem, keeping the order of `files`.
+fn index_by_stem(files: &[PathWithKey]) -> HashMap<&str, Vec<&PathBuf>> {
+    let mut index = HashMap::<&str, Vec<&PathBuf>>::new();
+
+    for file in files {
+        index.entry(file.key.as_str()).or_default().push(&file.path);
+    }
+
+    index
+}
+
+/// Pair the images and labels sharing a single file stem.
+fn pair_stem<L>(
+    file_metadata: &FileMetadata,
+    stem: String,
+    label_paths: &[&PathBuf],
+    image_paths: &[&PathBuf],
+    loader: &L,
+) -> Vec<PairingResult>
+where
+    L: Fn(&FileMetadata, &String) -> Result<YoloFile, YoloFileParseError> + Sync,
+{
     let mut pairs = Vec::new();
 
-    for stem in stems {
-        let mut image_paths_for_stem = image_filenames
+    let mut image_paths_for_stem = image_paths
+        .iter()
+        .map(|&image| image.clone())
+        .collect::<Vec<PathBuf>>();
+    image_paths_for_stem.sort_by(|a, b| a.to_string_lossy().cmp(&b.to_string_lossy()));
+
+    let mut image_paths_for_stem = image_paths_for_stem
+        .iter()
+        .map(|image| match image.to_str() {
+            Some(p) => Ok(p.to_string()),
+            None => Err(()),
+        })
+        .collect::<Vec<Result<String, ()>>>();
+    image_paths_for_stem.sort_by(|a, b| {
+        let a_str = a.as_ref().map(|s| s.as_str()).unwrap_or("");
+        let b_str = b.as_ref().map(|s| s.as_str()).unwrap_or("");
+        a_str.cmp(b_str)
+    });
+
+    let mut label_paths_for_stem = label_paths
+        .iter()
+        .map(|&label| label.clone())
+        .collect::<Vec<PathBuf>>();
+    label_paths_for_stem.sort_by(|a, b| a.to_string_lossy().cmp(&b.to_string_lossy()));
+
+    let mut label_paths_for_stem = label_paths_for_stem
+        .iter()
+        .map(|label| match label.to_str() {
+            Some(p) => Ok(p.to_string()),
+            None => Err(()),
+        })
+        .collect::<Vec<Result<String, ()>>>();
+    label_paths_for_stem.sort_by(|a, b| {
+        let a_str = a.as_ref().map(|s| s.as_str()).unwrap_or("");
+        let b_str = b.as_ref().map(|s| s.as_str()).unwrap_or("");
+        a_str.cmp(b_str)
+    });
+
+    // Images without a label file are paired with an empty label when
+    // they are kept as background images.
+    let (invalid_pairs, label_files) = if label_paths_for_stem.is_empty()
+        && file_metadata.background == BackgroundPolicy::EmptyOrMissingLabels
+    {
+        let label_files = image_paths_for_stem
             .iter()
-            .filter(|image| image.key == *stem)
-            .map(|image| image.path.clone())
-            .collect::<Vec<PathBuf>>();
-        image_paths_for_stem.sort_by(|a, b| a.to_string_lossy().cmp(&b.to_string_lossy()));
+            .map(|_| YoloFile::background(&stem))
+            .collect::<Vec<YoloFile>>();
+        (Vec::new(), label_files)
+    } else {
+        process_label_path(file_metadata, label_paths_for_stem, loader)
+    };
 
-        let mut image_paths_for_stem = image_paths_for_stem
-            .iter()
-            .map(|image| match image.to_str() {
-                Some(p) => Ok(p.to_string()),
-                None => Err(()),
-            })
-            .collect::<Vec<Result<String, ()>>>();
-        image_paths_for_stem.sort_by(|a, b| {
-            let a_str = a.as_ref().map(|s| s.as_str()).unwrap_or("");
-            let b_str = b.as_ref().map(|s| s.as_str()).unwrap_or("");
-            a_str.cmp(b_str)
-        });
-
-        let mut label_paths_for_stem = label_filenames
-            .iter()
-            .filter(|label| label.key == *stem)
-            .map(|label| label.path.clone())
-            .collect::<Vec<PathBuf>>();
-        label_paths_for_stem.sort_by(|a, b| a.to_string_lossy().cmp(&b.to_string_lossy()));
+    let unconfirmed_pairs = image_paths_for_stem.into_iter().zip_longest(label_files);
 
-        let mut label_paths_for_stem = label_paths_for_stem
-            .iter()
-            .map(|label| match label.to_str() {
-                Some(p) => Ok(p.to_string()),
-                None => Err(()),
-            })
-            .collect::<Vec<Result<String, ()>>>();
-        label_paths_for_stem.sort_by(|a, b| {
-            let a_str = a.as_ref().map(|s| s.as_str()).unwrap_or("");
-            let b_str = b.as_ref().map(|s| s.as_str()).unwrap_or("");
-            a_str.cmp(b_str)
-        });
-
-        let (invalid_pairs, valid_label_paths) =
-            process_label_path(&file_metadata, label_paths_for_stem);
-
-        let label_paths_for_stem = valid_label_paths
-            .into_iter()
-            .map(Ok)
-            .collect::<Vec<Result<String, ()>>>();
-
-        let unconfirmed_pairs = image_paths_for_stem
-            .into_iter()
-            .zip_longest(label_paths_for_stem.into_iter());
-
-        let mut primary_pair: Option<ImageLabelPair> = None;
-
-        for pair in unconfirmed_pairs {
-            let result = evaluate_pair(stem.clone(), pair.clone(), &file_metadata);
-
-            match result {
-                PairingResult::Valid(pair) => match primary_pair {
-                    Some(ref primary) => {
-                        let error = if primary.label_file != pair.label_file {
-                            PairingError::DuplicateLabelMismatch(DuplicateImageLabelPair {
-                                name: stem.clone(),
-                                primary: primary.clone(),
-                                duplicate: pair.clone(),
-                            })
-                        } else {
-                            PairingError::Duplicate(DuplicateImageLabelPair {
-                                name: stem.clone(),
-                                primary: primary.clone(),
-                                duplicate: pair.clone(),
-                            })
-                        };
-                        pairs.push(PairingResult::Invalid(error));
-                    }
-                    None => {
-                        primary_pair = Some(pair.clone());
-                        pairs.push(PairingResult::Valid(pair));
-                    }
-                },
-                PairingResult::Invalid(error) => {
+    let mut primary_pair: Option<ImageLabelPair> = None;
+
+    for pair in unconfirmed_pairs {
+        let result = evaluate_pair(stem.clone(), pair);
+
+        #[cfg(feature = "image-validation")]
+        let result = match result {
+            PairingResult::Valid(pair) => {
+                crate::image_check::check_pair(pair, file_metadata.image_validation)
+            }
+            invalid => invalid,
+        };
+
+        match result {
+            PairingResult::Valid(pair) => match primary_pair {
+                Some(ref primary) => {
+                    let error = if primary.label_file != pair.label_file {
+                        PairingError::DuplicateLabelMismatch(DuplicateImageLabelPair {
+                            name: stem.clone(),
+                            primary: primary.clone(),
+                            duplicate: pair.clone(),
+                        })
+                    } else {
+                        PairingError::Duplicate(DuplicateImageLabelPair {
+                            name: stem.clone(),
+                            primary: primary.clone(),
+                            duplicate: pair.clone(),
+                        })
+                    };
                     pairs.push(PairingResult::Invalid(error));
                 }
+                None => {
+                    primary_pair = Some(pair.clone());
+                    pairs.push(PairingResult::Valid(pair));
+                }
+            },
+            PairingResult::Invalid(error) => {
+                pairs.push(PairingResult::Invalid(error));
             }
         }
-
-        pairs.extend(invalid_pairs);
     }
 
+    pairs.extend(invalid_pairs);
+
     pairs
 }
 
@@ -112,14 +190,18 @@ pub fn pair(
 ///
 /// Each path is parsed to ensure the label file contents are valid
 /// according to the provided [`FileMetadata`]. Any parsing failures
-/// are returned as [`PairingResult::Invalid`] while successful paths
-/// are collected for pairing with images.
-pub fn process_label_path(
+/// are returned as [`PairingResult::Invalid`] while the parsed files
+/// are returned for pairing with images, so each file is read once.
+pub fn process_label_path<L>(
     file_metadata: &FileMetadata,
     label_paths_for_stem: Vec<Result<String, ()>>,
-) -> (Vec<PairingResult>, Vec<String>) {
+    loader: &L,
+) -> (Vec<PairingResult>, Vec<YoloFile>)
+where
+    L: Fn(&FileMetadata, &String) -> Result<YoloFile, YoloFileParseError>,
+{
     let mut invalid_pairs = Vec::<PairingResult>::new();
-    let mut valid_paths = Vec::<String>::new();
+    let mut label_files = Vec::<YoloFile>::new();
 
     if label_paths_for_stem.is_empty() {
         invalid_pairs.push(PairingResult::Invalid(
@@ -128,8 +210,8 @@ pub fn process_label_path(
     } else {
         for label_path in label_paths_for_stem {
             match label_path {
-                Ok(path) => match YoloFile::new(file_metadata, &path) {
-                    Ok(_) => valid_paths.push(path),
+                Ok(path) => match loader(file_metadata, &path) {
+                    Ok(label_file) => label_files.push(label_file),
                     Err(error) => invalid_pairs
                         .push(PairingResult::Invalid(PairingError::LabelFileError(error))),
                 },
@@ -140,46 +222,31 @@ pub fn process_label_path(
         }
     }
 
-    (invalid_pairs, valid_paths)
+    (invalid_pairs, label_files)
 }
 
 /// Build a [`PairingResult`] from a potential image/label pair.
+///
+/// Labels are already parsed by [`process_label_path`].
 pub fn evaluate_pair(
     stem: String,
-    pair: EitherOrBoth<Result<String, ()>>,
-    metadata: &FileMetadata,
+    pair: EitherOrBoth<Result<String, ()>, YoloFile>,
 ) -> PairingResult {
     match pair {
-        EitherOrBoth::Both(image_path, label_path) => match (image_path, label_path) {
-            (Ok(image_path), Ok(label_path)) => {
-                let label_file = match YoloFile::new(metadata, &label_path) {
-                    Ok(file) => Some(file),
-                    Err(error) => {
-                        return PairingResult::Invalid(PairingError::LabelFileError(error))
-                    }
-                };
-
-                PairingResult::Valid(ImageLabelPair {
-                    name: stem,
-                    image_path: Some(PathBuf::from(image_path)),
-                    label_file,
-                })
-            }
-            (Ok(image_path), Err(_)) => {
-                PairingResult::Invalid(PairingError::LabelFileMissing(image_path))
-            }
-            (Err(_), Ok(label_path)) => {
-                PairingResult::Invalid(PairingError::ImageFileMissing(label_path))
-            }
-            (Err(_), Err(_)) => PairingResult::Invalid(PairingError::BothFilesMissing),
+        EitherOrBoth::Both(image_path, label_file) => match image_path {
+            Ok(image_path) => PairingResult::Valid(ImageLabelPair {
+                name: stem,
+                image_path: Some(PathBuf::from(image_path)),
+                label_file: Some(label_file),
+            }),
+            Err(_) => PairingResult::Invalid(PairingError::ImageFileMissing(label_file.path)),
         },
         EitherOrBoth::Left(image_path) => match image_path {
             Ok(image_path) => PairingResult::Invalid(PairingError::LabelFileMissing(image_path)),
             Err(_) => PairingResult::Invalid(PairingError::LabelFileMissingUnableToUnwrapImagePath),
         },
-        EitherOrBoth::Right(label_path) => match label_path {
-            Ok(label_path) => PairingResult::Invalid(PairingError::ImageFileMissing(label_path)),
-            Err(_) => PairingResult::Invalid(PairingError::ImageFileMissingUnableToUnwrapLabelPath),
-        },
+        EitherOrBoth::Right(label_file) => {
+            PairingResult::Invalid(PairingError::ImageFileMissing(label_file.path))
+        }
     }
 }
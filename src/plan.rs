@@ -0,0 +1,325 @@
+use hashbrown::HashSet;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    export::{is_empty_root, place_file},
+    file_utils::{absolute_path, sha256_file},
+    manifest::Manifest,
+    report::SplitReport,
+    types::{LinkMode, OverwritePolicy},
+    ExportError,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Every operation an export performs, computed without touching disk.
+///
+/// Created by [`crate::YoloProjectExporter::plan`]. Serialize it to JSON
+/// to review an export before running it with [`ExportPlan::execute`].
+pub struct ExportPlan {
+    /// Root directory of the export.
+    pub root: PathBuf,
+    /// Directories created before any file is written.
+    pub directories: Vec<PathBuf>,
+    /// Location of the YOLO dataset YAML file.
+    pub yaml_path: PathBuf,
+    /// Contents of the YOLO dataset YAML file.
+    pub yaml: String,
+    /// Location of the split report.
+    pub split_report_path: PathBuf,
+    /// Images and class instances assigned to each split.
+    pub split_report: SplitReport,
+    /// Location of the manifest listing every exported file.
+    pub manifest_path: PathBuf,
+    /// What to do when the export root already holds files.
+    #[serde(default)]
+    pub overwrite: OverwritePolicy,
+    /// Files placed in or written to the export directory.
+    pub files: Vec<PlannedFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// A single file produced by an export.
+pub struct PlannedFile {
+    /// Split the file belongs to: `train`, `validation` or `test`.
+    pub split: String,
+    /// Stem of the image/label pair the file belongs to, if any.
+    pub pair: Option<String>,
+    /// File the exported file is created from, if any.
+    pub source: Option<PathBuf>,
+    /// Path of the exported file.
+    pub destination: PathBuf,
+    /// How the exported file is created.
+    pub operation: FileOperation,
+    /// Number of labelled instances in the pair or image list.
+    #[serde(default)]
+    pub label_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// How a [`PlannedFile`] is created.
+pub enum FileOperation {
+    /// Copy or link the source file.
+    Link(LinkMode),
+    /// Write the given contents.
+    Write(String),
+}
+
+impl ExportPlan {
+    /// Perform the planned export.
+    ///
+    /// After every file is in place a [`Manifest`] with the checksum, size
+    /// and label count of each one is written to `manifest_path`.
+    ///
+    /// Everything is written to the staging directory returned by
+    /// [`ExportPlan::staging_path`] first. Once every file is in place the
+    /// staging directory is renamed to the export root. A root that
+    /// already holds files is handled according to `overwrite`: the export
+    /// fails before anything is written, the root is replaced, or the
+    /// staged files are moved into it one at a time.
+    ///
+    /// If the export fails, the root is left untouched and the staging
+    /// directory is kept: running the export again resumes it, skipping
+    /// files whose size and SHA-256 already match their source. Staged
+    /// files that are not part of this plan are deleted first, so a
+    /// resumed export never carries files over from a different plan.
+    pub fn execute(&self) -> Result<(), ExportError> {
+        self.check_root()?;
+
+        let staging = self.staging_path();
+        let staged = |path: &Path| match path.strip_prefix(&self.root) {
+            Ok(relative) => staging.join(relative),
+            Err(_) => path.to_path_buf(),
+        };
+
+        for directory in &self.directories {
+            let directory = staged(directory);
+            fs::create_dir_all(&directory).map_err(|_| {
+                ExportError::UnableToCreateDirectory(directory.to_string_lossy().into_owned())
+            })?;
+        }
+
+        let planned = [
+            &self.yaml_path,
+            &self.split_report_path,
+            &self.manifest_path,
+        ]
+        .into_iter()
+        .chain(self.files.iter().map(|file| &file.destination))
+        .map(|path| staged(path))
+        .collect::<HashSet<PathBuf>>();
+        remove_unplanned(&staging, &planned)?;
+
+        let yaml_path = staged(&self.yaml_path);
+        fs::write(&yaml_path, &self.yaml)
+            .map_err(|_| ExportError::WriteFile(yaml_path.to_string_lossy().into()))?;
+
+        let split_report_path = staged(&self.split_report_path);
+        let split_report = serde_json::to_string_pretty(&self.split_report)
+            .map_err(|_| ExportError::WriteFile(split_report_path.to_string_lossy().into()))?;
+
+        fs::write(&split_report_path, split_report)
+            .map_err(|_| ExportError::WriteFile(split_report_path.to_string_lossy().into()))?;
+
+        for file in &self.files {
+            let destination = staged(&file.destination);
+
+            match (&file.operation, &file.source) {
+                (FileOperation::Link(link_mode), Some(source)) => {
+                    if !is_unchanged(source, &destination, *link_mode) {
+                        place_file(source, &destination, *link_mode)?;
+                    }
+                }
+                (FileOperation::Link(_), None) => {
+                    return Err(ExportError::FailedToUnwrapLabelPath);
+                }
+                (FileOperation::Write(contents), _) => {
+                    fs::write(&destination, contents).map_err(|_| {
+                        ExportError::WriteFile(destination.to_string_lossy().into())
+                    })?;
+                }
+            }
+        }
+
+        let manifest_path = staged(&self.manifest_path);
+        let manifest = Manifest::new(
+            &[&self.yaml_path, &self.split_report_path],
+            &self.files,
+            staged,
+        )?;
+        let manifest = serde_json::to_string_pretty(&manifest)
+            .map_err(|_| ExportError::WriteFile(manifest_path.to_string_lossy().into()))?;
+
+        fs::write(&manifest_path, manifest)
+            .map_err(|_| ExportError::WriteFile(manifest_path.to_string_lossy().into()))?;
+
+        self.promote(&staging)
+    }
+
+    /// Whether the manifest at `manifest_path` records a finished run of
+    /// this plan: the same files, created from the same sources.
+    pub(crate) fn is_complete(&self) -> bool {
+        let Some(manifest) = fs::read_to_string(&self.manifest_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<Manifest>(&contents).ok())
+        else {
+            return false;
+        };
+
+        let planned = [&self.yaml_path, &self.split_report_path]
+            .into_iter()
+            .map(|path| (path, None))
+            .chain(
+                self.files
+                    .iter()
+                    .map(|file| (&file.destination, file.source.as_ref())),
+            );
+        let recorded = manifest
+            .files
+            .iter()
+            .map(|entry| (&entry.destination, entry.source.as_ref()));
+
+        planned.eq(recorded)
+    }
+
+    /// Directory the export is staged in: `.<root name>.staging` next to
+    /// the export root.
+    pub fn staging_path(&self) -> PathBuf {
+        let root = absolute_path(&self.root)
+            .unwrap_or_else(|_| self.root.clone())
+            .components()
+            .collect::<PathBuf>();
+
+        let name = root
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        root.with_file_name(format!(".{}.staging", name))
+    }
+
+    fn check_root(&self) -> Result<(), ExportError> {
+        if self.overwrite == OverwritePolicy::FailIfNotEmpty && !is_empty_root(&self.root) {
+            return Err(ExportError::ExportRootNotEmpty(
+                self.root.to_string_lossy().into_owned(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn promote(&self, staging: &Path) -> Result<(), ExportError> {
+        if !is_empty_root(&self.root) {
+            match self.overwrite {
+                OverwritePolicy::FailIfNotEmpty => self.check_root()?,
+                OverwritePolicy::Clean => fs::remove_dir_all(&self.root).map_err(|_| {
+                    ExportError::UnableToCleanDirectory(self.root.to_string_lossy().into_owned())
+                })?,
+                OverwritePolicy::Merge => {
+                    move_into(staging, &self.root)?;
+                    return fs::remove_dir_all(staging)
+                        .map_err(|_| move_error(staging, &self.root));
+                }
+            }
+        }
+
+        let _ = fs::remove_dir(&self.root);
+        fs::rename(staging, &self.root).map_err(|_| move_error(staging, &self.root))
+    }
+}
+
+/// Whether `destination` already has the size and SHA-256 of `source`.
+///
+/// A symbolic link only counts as unchanged under the symbolic link
+/// modes, so copies and hard links replace links left by earlier runs.
+fn is_unchanged(source: &Path, destination: &Path, link_mode: LinkMode) -> bool {
+    let Ok(link_metadata) = fs::symlink_metadata(destination) else {
+        return false;
+    };
+
+    let links = matches!(
+        link_mode,
+        LinkMode::RelativeSymlink | LinkMode::AbsoluteSymlink
+    );
+    if link_metadata.file_type().is_symlink() != links {
+        return false;
+    }
+
+    let (Ok(source_metadata), Ok(destination_metadata)) =
+        (fs::metadata(source), fs::metadata(destination))
+    else {
+        return false;
+    };
+
+    if !destination_metadata.is_file() || source_metadata.len() != destination_metadata.len() {
+        return false;
+    }
+
+    matches!(
+        (sha256_file(source), sha256_file(destination)),
+        (Ok(source_hash), Ok(destination_hash)) if source_hash == destination_hash
+    )
+}
+
+/// Delete every file below `directory` that is not in `planned`.
+fn remove_unplanned(directory: &Path, planned: &HashSet<PathBuf>) -> Result<(), ExportError> {
+    let clean_error = || ExportError::UnableToCleanDirectory(directory.to_string_lossy().into());
+    let entries = fs::read_dir(directory).map_err(|_| clean_error())?;
+
+    for entry in entries {
+        let entry = entry.map_err(|_| clean_error())?;
+        let path = entry.path();
+
+        // Links to directories are removed like files, never followed.
+        let is_dir = entry
+            .file_type()
+            .map(|file_type| file_type.is_dir())
+            .unwrap_or(false);
+
+        if is_dir {
+            remove_unplanned(&path, planned)?;
+        } else if !planned.contains(&path) {
+            fs::remove_file(&path).map_err(|_| clean_error())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Move every file below `from` to the same relative path below `to`.
+fn move_into(from: &Path, to: &Path) -> Result<(), ExportError> {
+    let entries = fs::read_dir(from).map_err(|_| move_error(from, to))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|_| move_error(from, to))?;
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+
+        let is_dir = entry
+            .file_type()
+            .map(|file_type| file_type.is_dir())
+            .unwrap_or(false);
+
+        if is_dir {
+            fs::create_dir_all(&target).map_err(|_| {
+                ExportError::UnableToCreateDirectory(target.to_string_lossy().into_owned())
+            })?;
+            move_into(&source, &target)?;
+        } else {
+            fs::rename(&source, &target).map_err(|_| move_error(&source, &target))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn move_error(from: &Path, to: &Path) -> ExportError {
+    ExportError::FailedToMoveFile(
+        from.to_string_lossy().into_owned(),
+        to.to_string_lossy().into_owned(),
+    )
+}
@@ -1,8 +1,9 @@
 use crate::{
-    types::{PairingError, PairingResult},
+    types::{ImageLabelPair, PairingError, PairingResult},
     YoloFileParseError, YoloProject,
 };
 
+use hashbrown::HashMap;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -21,18 +22,55 @@ pub struct YoloDataQualityReport;
 
 impl YoloDataQualityReport {
     /// Collect all [`DataQualityItem`]s from a [`YoloProject`].
+    ///
+    /// A label file rejected with [`YoloFileParseError::InvalidRows`]
+    /// contributes one item per invalid row. Dropped rows of label files
+    /// in valid pairs are listed as well, and so are formatting notices
+    /// when `strict_formatting` is set.
     fn collect_items(project: &YoloProject) -> Vec<DataQualityItem> {
         let mut errors = Vec::<DataQualityItem>::new();
+        let strict_formatting = project.config.export.strict_formatting;
 
-        for error in project.data.pairs.iter() {
-            if let PairingResult::Invalid(pairing_error) = error {
-                let dq_item = DataQualityItem {
-                    source: Self::get_source_name(pairing_error),
-                    message: pairing_error.to_string(),
-                    data: pairing_error.clone(),
+        for result in project.data.pairs.iter() {
+            if let PairingResult::Valid(pair) = result {
+                let warnings = pair.label_file.iter().flat_map(|label| {
+                    let formatting = if strict_formatting {
+                        label.formatting.as_slice()
+                    } else {
+                        &[]
+                    };
+                    label.warnings.iter().chain(formatting)
+                });
+
+                for warning in warnings {
+                    let pairing_error = PairingError::LabelFileError(warning.clone());
+                    errors.push(DataQualityItem {
+                        source: Self::get_source_name(&pairing_error),
+                        message: pairing_error.to_string(),
+                        data: pairing_error,
+                    });
+                }
+            }
+
+            if let PairingResult::Invalid(pairing_error) = result {
+                let pairing_errors = match pairing_error {
+                    PairingError::LabelFileError(YoloFileParseError::InvalidRows {
+                        rows, ..
+                    }) => rows
+                        .iter()
+                        .cloned()
+                        .map(PairingError::LabelFileError)
+                        .collect(),
+                    _ => vec![pairing_error.clone()],
                 };
 
-                errors.push(dq_item);
+                for pairing_error in pairing_errors {
+                    errors.push(DataQualityItem {
+                        source: Self::get_source_name(&pairing_error),
+                        message: pairing_error.to_string(),
+                        data: pairing_error,
+                    });
+                }
             }
         }
 
@@ -89,6 +127,26 @@ impl YoloDataQualityReport {
                 YoloFileParseError::FailedToReadFile(_) => {
                     String::from("YoloFileParseError::FailedToReadFile")
                 }
+                YoloFileParseError::OddPolygonCoordinates(_) => {
+                    String::from("YoloFileParseError::OddPolygonCoordinates")
+                }
+                YoloFileParseError::TooFewPolygonPoints(_) => {
+                    String::from("YoloFileParseError::TooFewPolygonPoints")
+                }
+                YoloFileParseError::InvalidCornerOrder(_) => {
+                    String::from("YoloFileParseError::InvalidCornerOrder")
+                }
+                YoloFileParseError::NonConvexBox(_) => {
+                    String::from("YoloFileParseError::NonConvexBox")
+                }
+                YoloFileParseError::InvalidRows { .. } => {
+                    String::from("YoloFileParseError::InvalidRows")
+                }
+                YoloFileParseError::IrregularWhitespace(_) => {
+                    String::from("YoloFileParseError::IrregularWhitespace")
+                }
+                YoloFileParseError::Comment(_) => String::from("YoloFileParseError::Comment"),
+                YoloFileParseError::BlankLine(_) => String::from("YoloFileParseError::BlankLine"),
             },
             PairingError::BothFilesMissing => String::from("BothFilesMissing"),
             PairingError::LabelFileMissing(_) => String::from("LabelFileMissing"),
@@ -101,6 +159,131 @@ impl YoloDataQualityReport {
             }
             PairingError::Duplicate(_) => String::from("DuplicateImageLabelPair"),
             PairingError::DuplicateLabelMismatch(_) => String::from("DuplicateImageLabelMismatch"),
+            PairingError::UnmappableAnnotation(_) => String::from("UnmappableAnnotation"),
+            PairingError::UnreadableImage(_) => String::from("UnreadableImage"),
+            PairingError::TruncatedImage(_) => String::from("TruncatedImage"),
+            PairingError::ImageExtensionMismatch(_, _) => String::from("ImageExtensionMismatch"),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+/// Number of instances of a class in each split.
+pub struct ClassDistribution {
+    /// Class identifier.
+    pub class: isize,
+    /// Class name from the class map, or the id if it is not mapped.
+    pub name: String,
+    /// Instances in the training split.
+    pub train: usize,
+    /// Instances in the validation split.
+    pub validation: usize,
+    /// Instances in the test split.
+    pub test: usize,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+/// Summary of how an export distributed images and classes.
+///
+/// Written to `split_report.json` in the export root.
+pub struct SplitReport {
+    /// Images in the training split.
+    pub train_images: usize,
+    /// Images in the validation split.
+    pub validation_images: usize,
+    /// Images in the test split.
+    pub test_images: usize,
+    /// Background images, without labelled objects, in the training split.
+    #[serde(default)]
+    pub train_background_images: usize,
+    /// Background images in the validation split.
+    #[serde(default)]
+    pub validation_background_images: usize,
+    /// Background images in the test split.
+    #[serde(default)]
+    pub test_background_images: usize,
+    /// Share of all images that are background images.
+    #[serde(default)]
+    pub background_ratio: f32,
+    /// Per-class instance counts, ordered by class id.
+    pub classes: Vec<ClassDistribution>,
+}
+
+impl SplitReport {
+    /// Count the images and class instances of each split.
+    ///
+    /// Every class in `class_map` is listed, even if it has no instances.
+    pub fn new(
+        train: &[ImageLabelPair],
+        validation: &[ImageLabelPair],
+        test: &[ImageLabelPair],
+        class_map: &HashMap<isize, String>,
+    ) -> Self {
+        let mut classes = class_map
+            .iter()
+            .map(|(class, name)| {
+                (
+                    *class,
+                    ClassDistribution {
+                        class: *class,
+                        name: name.clone(),
+                        train: 0,
+                        validation: 0,
+                        test: 0,
+                    },
+                )
+            })
+            .collect::<HashMap<isize, ClassDistribution>>();
+
+        for (index, pairs) in [train, validation, test].iter().enumerate() {
+            let instances = pairs
+                .iter()
+                .filter_map(|pair| pair.label_file.as_ref())
+                .flat_map(|label_file| label_file.classes());
+
+            for class in instances {
+                let distribution = classes.entry(class).or_insert(ClassDistribution {
+                    class,
+                    name: class.to_string(),
+                    train: 0,
+                    validation: 0,
+                    test: 0,
+                });
+
+                match index {
+                    0 => distribution.train += 1,
+                    1 => distribution.validation += 1,
+                    _ => distribution.test += 1,
+                }
+            }
+        }
+
+        let mut classes = classes.into_values().collect::<Vec<ClassDistribution>>();
+        classes.sort_by_key(|distribution| distribution.class);
+
+        let background = [train, validation, test].map(|pairs| {
+            pairs
+                .iter()
+                .filter(|pair| match pair.label_file {
+                    Some(ref label_file) => label_file.is_background(),
+                    None => true,
+                })
+                .count()
+        });
+        let images = train.len() + validation.len() + test.len();
+
+        Self {
+            train_images: train.len(),
+            validation_images: validation.len(),
+            test_images: test.len(),
+            train_background_images: background[0],
+            validation_background_images: background[1],
+            test_background_images: background[2],
+            background_ratio: match images {
+                0 => 0.0,
+                _ => background.iter().sum::<usize>() as f32 / images as f32,
+            },
+            classes,
         }
     }
 }
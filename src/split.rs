@@ -0,0 +1,379 @@
+use hashbrown::HashMap;
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+use regex::Regex;
+
+use crate::{
+    types::{ImageLabelPair, Split, SplitGroup, SplitMode},
+    ExportError,
+};
+
+/// Pairs assigned to the training, validation and test splits.
+pub(crate) type SplitPairs = (
+    Vec<ImageLabelPair>,
+    Vec<ImageLabelPair>,
+    Vec<ImageLabelPair>,
+);
+
+/// Pairs that must be kept in the same split.
+struct Unit {
+    key: String,
+    pairs: Vec<ImageLabelPair>,
+}
+
+/// Divide pairs into training, validation and test sets.
+///
+/// When a [`SplitGroup`] is configured, pairs sharing a group key are
+/// assigned as one unit so a group never spans two splits.
+pub(crate) fn split_pairs(
+    pairs: Vec<ImageLabelPair>,
+    split: &Split,
+) -> Result<SplitPairs, ExportError> {
+    let units = group_pairs(pairs, split.group.as_ref())?;
+
+    let targets = match (split.mode, split.seed) {
+        (SplitMode::Stratified, _) => assign_stratified(&units, split),
+        (SplitMode::Random, Some(seed)) => assign_by_hash(&units, split, seed),
+        (SplitMode::Random, None) => assign_shuffled(&units, split),
+    };
+
+    let mut train_pairs = Vec::<ImageLabelPair>::new();
+    let mut validation_pairs = Vec::<ImageLabelPair>::new();
+    let mut test_pairs = Vec::<ImageLabelPair>::new();
+
+    for (unit, target) in units.into_iter().zip(targets) {
+        match target {
+            0 => train_pairs.extend(unit.pairs),
+            1 => validation_pairs.extend(unit.pairs),
+            _ => test_pairs.extend(unit.pairs),
+        }
+    }
+
+    Ok((train_pairs, validation_pairs, test_pairs))
+}
+
+/// Hold out a test set and divide the remaining pairs into `folds`
+/// partitions for cross-validation.
+///
+/// Units are ordered as in [`split_pairs`] and each is added to the
+/// partition with the fewest pairs. In stratified mode units holding
+/// rare classes are placed first so they are spread over the partitions.
+pub(crate) fn split_folds(
+    pairs: Vec<ImageLabelPair>,
+    split: &Split,
+    folds: usize,
+) -> Result<(Vec<Vec<ImageLabelPair>>, Vec<ImageLabelPair>), ExportError> {
+    let holdout = Split {
+        train: 1.0 - split.test,
+        validation: 0.0,
+        ..split.clone()
+    };
+
+    let (pairs, _, test_pairs) = split_pairs(pairs, &holdout)?;
+    let units = group_pairs(pairs, split.group.as_ref())?;
+
+    if folds < 2 || folds > units.len() {
+        return Err(ExportError::InvalidFoldCount(folds, units.len()));
+    }
+
+    let mut order = seeded_order(&units, split.seed);
+
+    if split.mode == SplitMode::Stratified {
+        let classes = ClassCounts::new(&units);
+        order.sort_by_key(|&index| classes.rarity(index));
+    }
+
+    let mut partitions = vec![Vec::<ImageLabelPair>::new(); folds];
+
+    for index in order {
+        let smallest = (0..folds)
+            .min_by_key(|&fold| partitions[fold].len())
+            .unwrap_or(0);
+        partitions[smallest].extend(units[index].pairs.iter().cloned());
+    }
+
+    Ok((partitions, test_pairs))
+}
+
+/// Collect pairs into units, keeping the order of first appearance.
+fn group_pairs(
+    pairs: Vec<ImageLabelPair>,
+    group: Option<&SplitGroup>,
+) -> Result<Vec<Unit>, ExportError> {
+    let pattern = match group {
+        Some(SplitGroup::StemPattern(pattern)) => {
+            Some(Regex::new(pattern).map_err(|e| ExportError::InvalidGroupPattern(e.to_string()))?)
+        }
+        _ => None,
+    };
+
+    let mut units = Vec::<Unit>::new();
+    let mut unit_index = HashMap::<String, usize>::new();
+
+    for pair in pairs {
+        let key = match group {
+            None => pair.name.clone(),
+            Some(SplitGroup::StemPattern(_)) => pattern
+                .as_ref()
+                .and_then(|pattern| pattern.captures(&pair.name))
+                .and_then(|captures| captures.get(1).or_else(|| captures.get(0)))
+                .map(|found| found.as_str().to_string())
+                .unwrap_or_else(|| pair.name.clone()),
+            Some(SplitGroup::ParentDirectory) => pair
+                .image_path
+                .as_ref()
+                .and_then(|path| path.parent())
+                .and_then(|parent| parent.file_name())
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| pair.name.clone()),
+        };
+
+        match unit_index.get(&key) {
+            Some(&index) => units[index].pairs.push(pair),
+            None => {
+                unit_index.insert(key.clone(), units.len());
+                units.push(Unit {
+                    key,
+                    pairs: vec![pair],
+                });
+            }
+        }
+    }
+
+    Ok(units)
+}
+
+/// Unit indices ordered by the seeded hash of their keys, or shuffled
+/// when no seed is set.
+fn seeded_order(units: &[Unit], seed: Option<u64>) -> Vec<usize> {
+    let mut order = (0..units.len()).collect::<Vec<usize>>();
+    match seed {
+        Some(seed) => order.sort_by_key(|&index| stem_hash(seed, &units[index].key)),
+        None => order.shuffle(&mut thread_rng()),
+    }
+    order
+}
+
+/// Number of images wanted in the training, validation and test splits.
+fn capacities(total: usize, split: &Split) -> [usize; 3] {
+    let num_test_pairs = ((split.test * total as f32).round() as usize).min(total);
+    let num_val_pairs =
+        ((split.validation * total as f32).round() as usize).min(total - num_test_pairs);
+
+    [
+        total - num_test_pairs - num_val_pairs,
+        num_val_pairs,
+        num_test_pairs,
+    ]
+}
+
+/// Shuffle the units and fill the test, then validation split up to
+/// their size. Remaining units go to training.
+fn assign_shuffled(units: &[Unit], split: &Split) -> Vec<usize> {
+    let total = units.iter().map(|unit| unit.pairs.len()).sum();
+    let capacities = capacities(total, split);
+
+    let order = seeded_order(units, None);
+
+    let mut targets = vec![0usize; units.len()];
+    let mut sizes = [0usize; 3];
+
+    for index in order {
+        let target = [2, 1]
+            .into_iter()
+            .find(|&target| sizes[target] < capacities[target])
+            .unwrap_or(0);
+
+        sizes[target] += units[index].pairs.len();
+        targets[index] = target;
+    }
+
+    targets
+}
+
+/// Assign every unit to a split based on the seeded hash of its key.
+///
+/// The hash is mapped onto `[0, 1)`; units below `test` go to the test
+/// split, units below `test + validation` to validation and the rest
+/// to training.
+fn assign_by_hash(units: &[Unit], split: &Split, seed: u64) -> Vec<usize> {
+    units
+        .iter()
+        .map(|unit| {
+            let position = unit_interval(stem_hash(seed, &unit.key));
+
+            if position < split.test as f64 {
+                2
+            } else if position < (split.test + split.validation) as f64 {
+                1
+            } else {
+                0
+            }
+        })
+        .collect()
+}
+
+/// Assign units so each split receives its share of every class.
+///
+/// Split sizes are computed as in the random mode. Units are placed
+/// starting with those holding the rarest classes, each into the split
+/// with free capacity that is furthest below its target for the rarest
+/// class of the unit. Ties go to the split with the most free room.
+/// Background images count as a class of their own.
+fn assign_stratified(units: &[Unit], split: &Split) -> Vec<usize> {
+    let mut order = seeded_order(units, split.seed);
+
+    let total = units.iter().map(|unit| unit.pairs.len()).sum::<usize>();
+    let capacities = capacities(total, split);
+
+    let classes = ClassCounts::new(units);
+    order.sort_by_key(|&index| classes.rarity(index));
+
+    let mut assigned = [
+        HashMap::<Stratum, usize>::new(),
+        HashMap::<Stratum, usize>::new(),
+        HashMap::<Stratum, usize>::new(),
+    ];
+    let mut sizes = [0usize; 3];
+    let mut targets = vec![0usize; units.len()];
+
+    for index in order {
+        let mut best: Option<(usize, f64, f64)> = None;
+
+        for target in 0..3 {
+            if sizes[target] >= capacities[target] {
+                continue;
+            }
+
+            let share = capacities[target] as f64 / total as f64;
+            let deficit = match classes.rarest[index] {
+                Some(class) => {
+                    let desired = classes.totals[&class] as f64 * share;
+                    desired - *assigned[target].get(&class).unwrap_or(&0) as f64
+                }
+                None => 0.0,
+            };
+            let free = (capacities[target] - sizes[target]) as f64 / capacities[target] as f64;
+
+            let is_better = match best {
+                None => true,
+                Some((_, best_deficit, best_free)) => {
+                    deficit > best_deficit + 1e-9
+                        || ((deficit - best_deficit).abs() <= 1e-9 && free > best_free)
+                }
+            };
+
+            if is_better {
+                best = Some((target, deficit, free));
+            }
+        }
+
+        let target = best.map(|(target, _, _)| target).unwrap_or(0);
+
+        for (class, count) in &classes.per_unit[index] {
+            *assigned[target].entry(*class).or_default() += count;
+        }
+        sizes[target] += units[index].pairs.len();
+        targets[index] = target;
+    }
+
+    targets
+}
+
+/// Group of labels whose share is balanced by stratified splits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Stratum {
+    /// Instances of a class.
+    Class(isize),
+    /// Images without labelled objects.
+    Background,
+}
+
+/// Class instance counts of a set of units.
+struct ClassCounts {
+    /// Instances of each class in every unit.
+    per_unit: Vec<HashMap<Stratum, usize>>,
+    /// Instances of each class over all units.
+    totals: HashMap<Stratum, usize>,
+    /// Least common class of every unit, if it has any pairs.
+    rarest: Vec<Option<Stratum>>,
+}
+
+impl ClassCounts {
+    fn new(units: &[Unit]) -> Self {
+        let per_unit = units
+            .iter()
+            .map(|unit| {
+                let mut counts = HashMap::<Stratum, usize>::new();
+                for pair in &unit.pairs {
+                    let classes = pair
+                        .label_file
+                        .as_ref()
+                        .map(|label_file| label_file.classes())
+                        .unwrap_or_default();
+
+                    if classes.is_empty() {
+                        *counts.entry(Stratum::Background).or_default() += 1;
+                    }
+
+                    for class in classes {
+                        *counts.entry(Stratum::Class(class)).or_default() += 1;
+                    }
+                }
+                counts
+            })
+            .collect::<Vec<HashMap<Stratum, usize>>>();
+
+        let mut totals = HashMap::<Stratum, usize>::new();
+        for counts in &per_unit {
+            for (class, count) in counts {
+                *totals.entry(*class).or_default() += count;
+            }
+        }
+
+        let rarest = per_unit
+            .iter()
+            .map(|counts| {
+                counts
+                    .keys()
+                    .min_by_key(|class| (totals[*class], **class))
+                    .copied()
+            })
+            .collect::<Vec<Option<Stratum>>>();
+
+        Self {
+            per_unit,
+            totals,
+            rarest,
+        }
+    }
+
+    /// Sort key placing units with the rarest classes first.
+    fn rarity(&self, index: usize) -> usize {
+        self.rarest[index]
+            .map(|class| self.totals[&class])
+            .unwrap_or(usize::MAX)
+    }
+}
+
+/// Hash a file stem together with a seed.
+///
+/// Uses FNV-1a followed by the SplitMix64 finalizer so the result is the
+/// same on every platform and Rust version, unlike the hashers in `std`.
+pub(crate) fn stem_hash(seed: u64, stem: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+
+    for byte in seed.to_le_bytes().iter().chain(stem.as_bytes()) {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+/// Map a hash onto `[0, 1)`.
+pub(crate) fn unit_interval(hash: u64) -> f64 {
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -14,6 +14,72 @@ pub struct Split {
     pub validation: f32,
     /// Portion of data to use for testing.
     pub test: f32,
+    /// Seed for a reproducible split.
+    ///
+    /// When set, every pair is assigned to a split by hashing its stem,
+    /// or its group key when grouping, together with the seed. The same
+    /// seed always gives the same split on every platform and adding new
+    /// images never moves existing ones to another split. The ratios are
+    /// then met on average rather than exactly. Without a seed the pairs
+    /// are shuffled randomly on every export.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// How pairs are distributed across the splits.
+    #[serde(default)]
+    pub mode: SplitMode,
+    /// Keep related pairs, e.g. frames of one video, in the same split.
+    #[serde(default)]
+    pub group: Option<SplitGroup>,
+}
+
+impl Default for Split {
+    fn default() -> Self {
+        Self {
+            train: 0.7,
+            validation: 0.2,
+            test: 0.1,
+            seed: None,
+            mode: SplitMode::default(),
+            group: None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// Strategy used to assign pairs to the train, validation and test splits.
+pub enum SplitMode {
+    /// Pairs are shuffled, or hashed by stem when a seed is set.
+    #[default]
+    Random,
+    /// Pairs are assigned so that every split receives its share of the
+    /// instances of each class.
+    ///
+    /// Images are processed from the rarest class to the most common one
+    /// and each is placed in the split that is furthest below its target
+    /// for the rarest class in the image. The number of images per split still
+    /// follows the configured ratios exactly. A seed only breaks ties, so
+    /// adding images may move existing ones between splits.
+    Stratified,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// How the group of a pair is determined when splitting.
+///
+/// Every pair of a group is assigned to the same split, so the split
+/// sizes follow the configured ratios only as closely as the group
+/// sizes allow. Pairs that do not belong to a group are split on their
+/// own.
+pub enum SplitGroup {
+    /// Regular expression matched against the file stem.
+    ///
+    /// The first capture group is the group key, or the whole match if
+    /// the expression has no groups. For `clip_(\d+)_frame_\d+` the
+    /// stems `clip_7_frame_1` and `clip_7_frame_2` share the key `7`.
+    StemPattern(String),
+    /// Name of the directory that contains the image.
+    ParentDirectory,
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -23,10 +89,203 @@ pub struct Export {
     pub paths: Paths,
     /// Mapping of class id to class name.
     pub class_map: HashMap<isize, String>,
-    /// Bounding box overlap tolerance used for duplicate detection.
+    /// Tolerance used to detect duplicate labels in a file. `0.0` turns
+    /// the check off.
+    ///
+    /// Its meaning depends on the label type. Boxes, including those of
+    /// poses, and polygons are duplicates when every edge or vertex is
+    /// within this distance, in normalized coordinates, of an earlier
+    /// label. Oriented boxes are duplicates when their IoU is at least
+    /// `1.0 - duplicate_tolerance`.
     pub duplicate_tolerance: f32,
     /// Train/val/test ratio.
     pub split: Split,
+    /// Keypoint layout for pose estimation labels.
+    #[serde(default)]
+    pub keypoints: Option<Keypoints>,
+    /// Parse labels as oriented bounding boxes (YOLO-OBB).
+    ///
+    /// Duplicate boxes are detected by their rotated overlap: two boxes
+    /// are duplicates when their IoU is at least `1.0 - duplicate_tolerance`.
+    #[serde(default)]
+    pub oriented_bounding_boxes: bool,
+    /// How image and label files are placed in the export directory.
+    #[serde(default)]
+    pub link_mode: LinkMode,
+    /// Whether files are placed in split directories or only listed.
+    #[serde(default)]
+    pub layout: ExportLayout,
+    /// What to do when the export root already holds files.
+    #[serde(default)]
+    pub overwrite: OverwritePolicy,
+    /// How label rows that fail validation are handled.
+    #[serde(default)]
+    pub row_errors: RowErrorPolicy,
+    /// Report tabs, repeated or trailing whitespace, comments and blank
+    /// lines in label files as warnings. They are accepted either way.
+    /// Only YOLO text labels have rows to check; XML whitespace in VOC
+    /// files is not significant.
+    #[serde(default)]
+    pub strict_formatting: bool,
+    /// Whether images without labelled objects are kept as background
+    /// images.
+    #[serde(default)]
+    pub background: BackgroundPolicy,
+    /// How image files are verified while pairing.
+    #[serde(default)]
+    pub image_validation: ImageValidation,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// How image files are verified while pairing.
+///
+/// Images failing a check are reported as invalid pairs. Checks need the
+/// `image-validation` feature; loading a project with checks enabled
+/// fails without it.
+pub enum ImageValidation {
+    /// Only check that an image exists.
+    #[default]
+    Off,
+    /// Read the header and the end of every image. Catches unreadable
+    /// and truncated files and files whose format does not match their
+    /// extension.
+    Header,
+    /// Run the header checks and decode every image completely.
+    Decode,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// Which images without labelled objects are kept as background images.
+///
+/// Background images are exported with an empty label file. They are
+/// counted in [`crate::SplitReport`] and stratified splits spread them
+/// like a class of their own.
+pub enum BackgroundPolicy {
+    /// Reject empty label files with
+    /// [`crate::YoloFileParseError::EmptyFile`] and report images
+    /// without a label file.
+    #[default]
+    Reject,
+    /// Keep images whose label file is empty or only holds comments and
+    /// blank lines.
+    EmptyLabels,
+    /// Also keep images that have no label file at all.
+    EmptyOrMissingLabels,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// How label rows that fail validation are handled.
+pub enum RowErrorPolicy {
+    /// Reject the label file at its first invalid row.
+    #[default]
+    Reject,
+    /// Check every row and reject the label file with all invalid rows
+    /// listed in [`crate::YoloFileParseError::InvalidRows`].
+    ReportAll,
+    /// Drop invalid rows and keep the label file with the remaining rows.
+    /// Dropped rows are listed in [`crate::YoloFile::warnings`]. A file
+    /// without any valid row is rejected as with `ReportAll`.
+    DropRows,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// How an export treats an existing, non-empty export root.
+pub enum OverwritePolicy {
+    /// Refuse to export with [`crate::ExportError::ExportRootNotEmpty`].
+    FailIfNotEmpty,
+    /// Delete the existing root and replace it with the new export.
+    Clean,
+    /// Write into the existing root. Files with the same name are
+    /// replaced, all others are kept.
+    ///
+    /// Files of an earlier export that are not part of this one are kept
+    /// too, so an image that moved to another split since then is found
+    /// in both and leaks between training and evaluation. Use
+    /// [`OverwritePolicy::Clean`] when the split may have changed.
+    #[default]
+    Merge,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// Shape of the exported dataset.
+pub enum ExportLayout {
+    /// Images and labels are placed in the train, validation and test
+    /// directories of [`Paths`].
+    #[default]
+    Directories,
+    /// No files are copied. `train.txt`, `val.txt` and `test.txt` list
+    /// the absolute paths of the original images of each split and the
+    /// YAML file points at these lists.
+    ///
+    /// YOLO tooling finds the label of an image by replacing the last
+    /// `images` directory in its path with `labels`, so the source
+    /// dataset must use that layout. Exports of labels stored elsewhere,
+    /// of converted labels, of labels with dropped rows and of labels
+    /// with comments are rejected.
+    FileLists,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// How exported files refer to their source files.
+pub enum LinkMode {
+    /// Copy every file.
+    #[default]
+    Copy,
+    /// Hard link every file, copying when source and export directory
+    /// are on different file systems.
+    Hardlink,
+    /// Symbolic link relative to the directory holding the link.
+    RelativeSymlink,
+    /// Symbolic link to the absolute path of the source file.
+    AbsoluteSymlink,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+/// Layout of the keypoints stored on every pose label row.
+pub struct Keypoints {
+    /// Number of keypoints per row.
+    pub count: usize,
+    /// Whether each keypoint has a visibility flag after its coordinates.
+    pub visibility: bool,
+    /// Keypoint index swapped with each keypoint on horizontal flips.
+    #[serde(default)]
+    pub flip_idx: Option<Vec<usize>>,
+}
+
+impl Keypoints {
+    /// Check that `flip_idx` maps every keypoint to a keypoint.
+    ///
+    /// YOLO tooling rejects a `flip_idx` that does not have one entry per
+    /// keypoint or that refers to a keypoint that does not exist.
+    pub fn check_flip_idx(&self) -> Result<(), ExportError> {
+        let Some(flip_idx) = &self.flip_idx else {
+            return Ok(());
+        };
+
+        if flip_idx.len() != self.count {
+            return Err(ExportError::FlipIdxLength(flip_idx.len(), self.count));
+        }
+
+        match flip_idx.iter().find(|&&index| index >= self.count) {
+            Some(&index) => Err(ExportError::FlipIdxOutOfRange(index, self.count)),
+            None => Ok(()),
+        }
+    }
+
+    /// Number of values stored for each keypoint.
+    pub fn dimensions(&self) -> usize {
+        if self.visibility {
+            3
+        } else {
+            2
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -63,6 +322,14 @@ impl Paths {
         self.root.clone()
     }
 
+    /// Paths of one cross-validation fold, rooted at `<root>/fold_<index>`.
+    pub fn for_fold(&self, index: usize) -> Paths {
+        Paths {
+            root: self.root.join(format!("fold_{}", index)),
+            ..self.clone()
+        }
+    }
+
     /// Path to the training images directory.
     pub fn get_train_images_path(&self) -> PathBuf {
         self.root.join(&self.train).join("images")
@@ -150,6 +417,16 @@ pub struct SourcePaths {
     pub images: String,
     /// Directory containing label files.
     pub labels: String,
+    /// File caching parsed labels and pairing results between loads.
+    ///
+    /// Label files whose size and modification time are unchanged are
+    /// not parsed again, and pairing is skipped entirely while no image
+    /// or label file was added, removed or changed. The cache is rebuilt
+    /// when the class map, duplicate tolerance or other label settings
+    /// change. Failing to write the cache is logged and does not fail the
+    /// load.
+    #[serde(default)]
+    pub cache: Option<PathBuf>,
 }
 
 impl Default for SourcePaths {
@@ -157,6 +434,7 @@ impl Default for SourcePaths {
         Self {
             images: "images".to_string(),
             labels: "labels".to_string(),
+            cache: None,
         }
     }
 }
@@ -170,12 +448,25 @@ pub struct YoloClass {
     pub name: String,
 }
 
+#[derive(Default)]
 /// Parameters used when validating label files.
 pub struct FileMetadata {
     /// Allowed classes for labels.
     pub classes: Vec<YoloClass>,
-    /// Tolerance for bounding box duplication.
+    /// Tolerance for duplicate labels: a per-edge or per-vertex distance
+    /// for boxes and polygons and `1.0 - IoU` for oriented boxes. See
+    /// [`Export::duplicate_tolerance`].
     pub duplicate_tolerance: f32,
+    /// Keypoint layout expected on pose label rows.
+    pub keypoints: Option<Keypoints>,
+    /// Whether rows are oriented bounding boxes.
+    pub oriented_bounding_boxes: bool,
+    /// How rows that fail validation are handled.
+    pub row_errors: RowErrorPolicy,
+    /// Which images without labelled objects are kept.
+    pub background: BackgroundPolicy,
+    /// How image files are verified while pairing.
+    pub image_validation: ImageValidation,
 }
 
 /// Configuration for a YOLO project.
@@ -202,11 +493,16 @@ impl Default for YoloProjectConfig {
                 paths: Paths::default(),
                 class_map: HashMap::new(),
                 duplicate_tolerance: 0.0,
-                split: Split {
-                    train: 0.7,
-                    validation: 0.2,
-                    test: 0.1,
-                },
+                split: Split::default(),
+                keypoints: None,
+                oriented_bounding_boxes: false,
+                link_mode: LinkMode::Copy,
+                layout: ExportLayout::Directories,
+                overwrite: OverwritePolicy::Merge,
+                row_errors: RowErrorPolicy::Reject,
+                strict_formatting: false,
+                background: BackgroundPolicy::Reject,
+                image_validation: ImageValidation::Off,
             },
         }
     }
@@ -251,6 +547,35 @@ impl std::fmt::Display for DuplicateImageLabelPair {
     }
 }
 
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+/// An annotation from an imported dataset that could not be converted to YOLO.
+pub struct UnmappableAnnotation {
+    /// Annotation file the entry was read from.
+    pub path: String,
+    /// Identifier of the annotation within the file, if it has one.
+    pub annotation: Option<String>,
+    /// Image the annotation belongs to, if known.
+    pub image: Option<String>,
+    /// Why the annotation could not be converted.
+    pub reason: String,
+}
+
+impl std::fmt::Display for UnmappableAnnotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unmappable annotation in '{}'", self.path)?;
+
+        if let Some(annotation) = &self.annotation {
+            write!(f, " (annotation {})", annotation)?;
+        }
+
+        if let Some(image) = &self.image {
+            write!(f, " for image '{}'", image)?;
+        }
+
+        write!(f, ": {}", self.reason)
+    }
+}
+
 #[derive(Error, Clone, PartialEq, Debug, Serialize, Deserialize)]
 /// Reasons why a stem could not be paired.
 pub enum PairingError {
@@ -262,6 +587,10 @@ pub enum PairingError {
     ImageFileMissingUnableToUnwrapLabelPath,
     Duplicate(DuplicateImageLabelPair),
     DuplicateLabelMismatch(DuplicateImageLabelPair),
+    UnmappableAnnotation(UnmappableAnnotation),
+    UnreadableImage(String),
+    TruncatedImage(String),
+    ImageExtensionMismatch(String, String),
 }
 
 impl std::fmt::Display for PairingError {
@@ -289,12 +618,25 @@ impl std::fmt::Display for PairingError {
             PairingError::DuplicateLabelMismatch(_) => {
                 write!(f, "Duplicate image with differing label files")
             }
+            PairingError::UnmappableAnnotation(annotation) => {
+                write!(f, "{}", annotation)
+            }
+            PairingError::UnreadableImage(path) => {
+                write!(f, "Unreadable image: {}", path)
+            }
+            PairingError::TruncatedImage(path) => {
+                write!(f, "Truncated image: {}", path)
+            }
+            PairingError::ImageExtensionMismatch(path, format) => {
+                write!(f, "Image '{}' is a {} file", path, format)
+            }
         }
     }
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 /// Result of attempting to pair an image and label file.
+#[allow(clippy::large_enum_variant)]
 pub enum PairingResult {
     Valid(ImageLabelPair),
     Invalid(PairingError),
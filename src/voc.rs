@@ -0,0 +1,370 @@
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, read_to_string},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    export::{place_file, prepare_root},
+    file_utils::get_file_stem,
+    types::{BackgroundPolicy, FileMetadata, ImageLabelPair, RowErrorPolicy},
+    ExportError, LabelSource, YoloEntry, YoloFile, YoloFileParseError, YoloFileParseErrorDetails,
+    YoloProject, YoloProjectExporter,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename = "annotation")]
+/// Root element of a Pascal VOC XML annotation file.
+pub struct VocAnnotation {
+    /// Directory of the image, if recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+    /// File name of the annotated image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    /// Dimensions of the annotated image.
+    pub size: VocSize,
+    /// Objects annotated in the image.
+    #[serde(default)]
+    pub object: Vec<VocObject>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// The `<size>` element of a VOC annotation.
+pub struct VocSize {
+    /// Image width in pixels.
+    pub width: u32,
+    /// Image height in pixels.
+    pub height: u32,
+    /// Number of color channels.
+    #[serde(default)]
+    pub depth: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// An `<object>` element of a VOC annotation.
+pub struct VocObject {
+    /// Class name of the object.
+    pub name: String,
+    /// Pose of the object, e.g. `Unspecified`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pose: Option<String>,
+    /// Whether the object is truncated by the image border.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncated: Option<u8>,
+    /// Whether the object is hard to recognize.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficult: Option<u8>,
+    /// Bounding box of the object.
+    pub bndbox: VocBndBox,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// The `<bndbox>` element of a VOC object, in pixels.
+pub struct VocBndBox {
+    /// Left edge of the box.
+    pub xmin: f32,
+    /// Top edge of the box.
+    pub ymin: f32,
+    /// Right edge of the box.
+    pub xmax: f32,
+    /// Bottom edge of the box.
+    pub ymax: f32,
+}
+
+impl YoloFile {
+    /// Read and validate a Pascal VOC XML annotation file.
+    ///
+    /// Boxes are normalized with the `<size>` of the annotation and each
+    /// `<name>` is looked up in the configured classes. Object indices are
+    /// reported as rows in errors and invalid objects are handled
+    /// according to `metadata.row_errors`, like rows of a YOLO file.
+    pub fn from_voc(
+        metadata: &FileMetadata,
+        path: &String,
+    ) -> Result<YoloFile, YoloFileParseError> {
+        let details = |row: Option<usize>| YoloFileParseErrorDetails {
+            path: path.to_string(),
+            class: None,
+            row,
+            other_row: None,
+            column: None,
+            value: None,
+        };
+
+        let file = read_to_string(path)
+            .map_err(|_| YoloFileParseError::FailedToReadFile(details(None)))?;
+
+        let annotation: VocAnnotation = quick_xml::de::from_str(&file)
+            .map_err(|_| YoloFileParseError::InvalidFormat(details(None)))?;
+
+        if annotation.size.width == 0 || annotation.size.height == 0 {
+            return Err(YoloFileParseError::InvalidFormat(details(None)));
+        }
+
+        if annotation.object.is_empty() && metadata.background == BackgroundPolicy::Reject {
+            return Err(YoloFileParseError::EmptyFile(details(None)));
+        }
+
+        let width = annotation.size.width as f32;
+        let height = annotation.size.height as f32;
+
+        let mut entries = Vec::<(usize, YoloEntry)>::new();
+        let mut row_errors = Vec::<YoloFileParseError>::new();
+
+        for (index, object) in annotation.object.iter().enumerate() {
+            match Self::parse_voc_object(metadata, &details, index, object, width, height, &entries)
+            {
+                Ok(entry) => entries.push((index, entry)),
+                Err(error) => {
+                    row_errors.push(error);
+                    if metadata.row_errors == RowErrorPolicy::Reject {
+                        break;
+                    }
+                }
+            }
+        }
+
+        let stem = get_file_stem(Path::new(path))
+            .map_err(|_| YoloFileParseError::FailedToGetFileStem(details(None)))?
+            .to_string();
+
+        let yolo_file = YoloFile {
+            stem,
+            path: path.to_string(),
+            entries: entries.into_iter().map(|(_, entry)| entry).collect(),
+            polygons: vec![],
+            poses: vec![],
+            oriented_boxes: vec![],
+            source: LabelSource::Voc,
+            warnings: vec![],
+            formatting: vec![],
+        };
+
+        Self::apply_row_errors(metadata, path, yolo_file, row_errors)
+    }
+
+    /// Validate a single `<object>` and convert it to a normalized entry.
+    ///
+    /// `entries` holds the object index and entry of every valid object
+    /// before this one.
+    fn parse_voc_object(
+        metadata: &FileMetadata,
+        details: &impl Fn(Option<usize>) -> YoloFileParseErrorDetails,
+        index: usize,
+        object: &VocObject,
+        width: f32,
+        height: f32,
+        entries: &[(usize, YoloEntry)],
+    ) -> Result<YoloEntry, YoloFileParseError> {
+        let tolerance = metadata.duplicate_tolerance;
+
+        let class = metadata
+            .classes
+            .iter()
+            .find(|class| class.name == object.name)
+            .map(|class| class.id)
+            .ok_or_else(|| {
+                YoloFileParseError::ClassIdNotFound(YoloFileParseErrorDetails {
+                    class: Some(object.name.clone()),
+                    column: Some("name".to_string()),
+                    ..details(Some(index))
+                })
+            })?;
+
+        let bndbox = &object.bndbox;
+        let columns = [
+            (
+                "xmin",
+                bndbox.xmin,
+                bndbox.xmin >= 0.0 && bndbox.xmin < bndbox.xmax,
+            ),
+            (
+                "ymin",
+                bndbox.ymin,
+                bndbox.ymin >= 0.0 && bndbox.ymin < bndbox.ymax,
+            ),
+            ("xmax", bndbox.xmax, bndbox.xmax <= width),
+            ("ymax", bndbox.ymax, bndbox.ymax <= height),
+        ];
+
+        for (column, value, in_range) in columns {
+            if !in_range {
+                return Err(YoloFileParseError::LabelDataOutOfRange(
+                    YoloFileParseErrorDetails {
+                        column: Some(column.to_string()),
+                        value: Some(value),
+                        ..details(Some(index))
+                    },
+                ));
+            }
+        }
+
+        let entry = YoloEntry {
+            class,
+            x_center: (bndbox.xmin + bndbox.xmax) / 2.0 / width,
+            y_center: (bndbox.ymin + bndbox.ymax) / 2.0 / height,
+            width: (bndbox.xmax - bndbox.xmin) / width,
+            height: (bndbox.ymax - bndbox.ymin) / height,
+        };
+
+        if tolerance > 0.0 {
+            if let Some((prev_index, _)) = entries
+                .iter()
+                .find(|(_, prev_entry)| prev_entry.is_duplicate_of(&entry, tolerance))
+            {
+                return Err(YoloFileParseError::DuplicateEntries(
+                    YoloFileParseErrorDetails {
+                        other_row: Some(index),
+                        ..details(Some(*prev_index))
+                    },
+                ));
+            }
+        }
+
+        Ok(entry)
+    }
+}
+
+/// Writes a [`YoloProject`] as Pascal VOC XML files.
+///
+/// The export uses the same [`crate::Paths`] layout and
+/// [`crate::Split`] as [`YoloProjectExporter::export`], but every label is
+/// written as `<name>.xml` instead of a YOLO text file. Pixel boxes are
+/// computed from the dimensions read from each image file. VOC only holds
+/// boxes, so polygons, poses and oriented boxes are written as their
+/// bounding rectangles.
+pub struct VocExporter;
+
+impl VocExporter {
+    /// Export the project to VOC files according to its configuration.
+    pub fn export(project: YoloProject) -> Result<(), ExportError> {
+        let paths = &project.config.export.paths;
+        let class_map = &project.config.export.class_map;
+
+        prepare_root(&paths.get_root(), project.config.export.overwrite)?;
+        paths.create_all_directories()?;
+
+        let (train_pairs, validation_pairs, test_pairs) = YoloProjectExporter::split_pairs(
+            project.get_valid_pairs(),
+            project.config.export.split.clone(),
+        )?;
+
+        let splits = vec![
+            (
+                paths.get_train_images_path(),
+                paths.get_train_label_images_path(),
+                train_pairs,
+            ),
+            (
+                paths.get_validation_images_path(),
+                paths.get_validation_label_images_path(),
+                validation_pairs,
+            ),
+            (
+                paths.get_test_images_path(),
+                paths.get_test_label_images_path(),
+                test_pairs,
+            ),
+        ];
+
+        for (images_path, labels_path, pairs) in splits {
+            for pair in pairs {
+                let source = pair
+                    .image_path
+                    .as_ref()
+                    .ok_or(ExportError::FailedToUnwrapLabelPath)?;
+
+                let annotation = Self::to_annotation(&pair, class_map)?;
+                let file_name = annotation.filename.clone().unwrap_or_default();
+                let destination = images_path.join(&file_name);
+
+                place_file(source, &destination, project.config.export.link_mode)?;
+
+                let xml_path = labels_path.join(PathBuf::from(&pair.name).with_extension("xml"));
+
+                let mut xml = String::new();
+                let mut serializer = quick_xml::se::Serializer::new(&mut xml);
+                serializer.indent(' ', 2);
+
+                annotation
+                    .serialize(serializer)
+                    .map_err(|_| ExportError::WriteFile(xml_path.to_string_lossy().into()))?;
+
+                fs::write(&xml_path, xml)
+                    .map_err(|_| ExportError::WriteFile(xml_path.to_string_lossy().into()))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Convert an image/label pair into a [`VocAnnotation`].
+    ///
+    /// The image keeps the file name `<stem>.<extension>` it is exported
+    /// under. Entries whose class is missing from `class_map` are named by
+    /// their numeric id.
+    pub fn to_annotation(
+        pair: &ImageLabelPair,
+        class_map: &HashMap<isize, String>,
+    ) -> Result<VocAnnotation, ExportError> {
+        let image_path = pair
+            .image_path
+            .as_ref()
+            .ok_or(ExportError::FailedToUnwrapLabelPath)?;
+
+        let size = imagesize::size(image_path).map_err(|_| {
+            ExportError::ReadImageDimensions(image_path.to_string_lossy().into_owned())
+        })?;
+
+        let width = size.width as f32;
+        let height = size.height as f32;
+
+        let extension = image_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("");
+
+        let entries = pair
+            .label_file
+            .as_ref()
+            .map(|label_file| label_file.bounding_boxes())
+            .unwrap_or_default();
+
+        let object = entries
+            .iter()
+            .map(|entry| VocObject {
+                name: class_map
+                    .get(&entry.class)
+                    .cloned()
+                    .unwrap_or_else(|| entry.class.to_string()),
+                pose: Some("Unspecified".to_string()),
+                truncated: Some(0),
+                difficult: Some(0),
+                bndbox: VocBndBox {
+                    xmin: (entry.x_center - entry.width / 2.0) * width,
+                    ymin: (entry.y_center - entry.height / 2.0) * height,
+                    xmax: (entry.x_center + entry.width / 2.0) * width,
+                    ymax: (entry.y_center + entry.height / 2.0) * height,
+                },
+            })
+            .collect::<Vec<VocObject>>();
+
+        Ok(VocAnnotation {
+            folder: None,
+            filename: Some(
+                PathBuf::from(&pair.name)
+                    .with_extension(extension)
+                    .to_string_lossy()
+                    .into_owned(),
+            ),
+            size: VocSize {
+                width: size.width as u32,
+                height: size.height as u32,
+                depth: 3,
+            },
+            object,
+        })
+    }
+}
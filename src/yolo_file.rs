@@ -11,13 +11,33 @@
    <y_center>: The normalized y-coordinate of the bounding box center.
    <width>: The normalized width of the bounding box.
    <height>: The normalized height of the bounding box.
+
+   Segmentation labels store a polygon instead of a bounding box:
+
+   <class> <x1> <y1> <x2> <y2> ... <xn> <yn>
+   Every point is normalized 0.0 - 1.0 and at least three points are required.
+
+   Pose labels append a fixed number of keypoints to the bounding box:
+
+   <class> <x_center> <y_center> <width> <height> <px1> <py1> [<v1>] ...
+   The keypoint count and whether a visibility flag is present come from the
+   project configuration.
+
+   Oriented bounding boxes (YOLO-OBB) store the four corners of the box:
+
+   <class> <x1> <y1> <x2> <y2> <x3> <y3> <x4> <y4>
+   Corners must follow each other around the box and form a convex shape.
 */
 
 use serde::{Deserialize, Serialize};
 use std::{fs::read_to_string, path::Path};
 use thiserror::Error;
 
-use crate::{file_utils::get_file_stem, types::FileMetadata};
+use crate::{
+    file_utils::get_file_stem,
+    geometry,
+    types::{BackgroundPolicy, FileMetadata, Keypoints, RowErrorPolicy},
+};
 
 /// Errors that can occur when parsing a YOLO label file.
 
@@ -28,20 +48,41 @@ pub enum YoloFileParseError {
     InvalidFormat(YoloFileParseErrorDetails),
     #[error("File '{}' is empty", .0.path)]
     EmptyFile(YoloFileParseErrorDetails),
-    #[error("Duplicate entries found in file '{}' on row {} and row {}", .0.path, .0.row.unwrap(), .0.other_row.unwrap())]
+    #[error("Duplicate entries found in file '{}' on row {} and row {}", .0.path, display_or_unknown(&.0.row), display_or_unknown(&.0.other_row))]
     DuplicateEntries(YoloFileParseErrorDetails),
-    #[error("Unable to parse value '{}' in file '{}' on line {}", .0.class.clone().unwrap(), .0.path, .0.row.unwrap())]
+    #[error("Unable to parse value '{}' in file '{}' on line {}", display_or_unknown(&.0.class), .0.path, display_or_unknown(&.0.row))]
     FailedToParseClassId(YoloFileParseErrorDetails),
-    #[error("Invalid class id '{}' in file '{}'", .0.class.clone().unwrap(), .0.path)]
+    #[error("Invalid class id '{}' in file '{}'", display_or_unknown(&.0.class), .0.path)]
     ClassIdNotFound(YoloFileParseErrorDetails),
-    #[error("Invalid data value for '{}' in file '{}' on line {}.  Value is '{}'", .0.column.clone().unwrap(), .0.path, .0.row.unwrap(), .0.value.unwrap())]
+    #[error("Invalid data value for '{}' in file '{}' on line {}.  Value is '{}'", display_or_unknown(&.0.column), .0.path, display_or_unknown(&.0.row), display_or_unknown(&.0.value))]
     LabelDataOutOfRange(YoloFileParseErrorDetails),
-    #[error("Failed to parse '{}' column with value of '{}' on line {} in file '{}'", .0.column.clone().unwrap(), .0.class.clone().unwrap(), .0.row.unwrap(), .0.path)]
+    #[error("Failed to parse '{}' column with value of '{}' on line {} in file '{}'", display_or_unknown(&.0.column), display_or_unknown(&.0.class), display_or_unknown(&.0.row), .0.path)]
     FailedToParseColumn(YoloFileParseErrorDetails),
     #[error("Failed to get file stem for file '{}'", .0.path)]
     FailedToGetFileStem(YoloFileParseErrorDetails),
     #[error("Failed to read file '{}'", .0.path)]
     FailedToReadFile(YoloFileParseErrorDetails),
+    #[error("Polygon in file '{}' on line {} has an odd number of coordinates", .0.path, display_or_unknown(&.0.row))]
+    OddPolygonCoordinates(YoloFileParseErrorDetails),
+    #[error("Polygon in file '{}' on line {} has fewer than three points", .0.path, display_or_unknown(&.0.row))]
+    TooFewPolygonPoints(YoloFileParseErrorDetails),
+    #[error("Oriented box corners in file '{}' on line {} are not in order around the box", .0.path, display_or_unknown(&.0.row))]
+    InvalidCornerOrder(YoloFileParseErrorDetails),
+    #[error("Oriented box in file '{}' on line {} is not convex", .0.path, display_or_unknown(&.0.row))]
+    NonConvexBox(YoloFileParseErrorDetails),
+    #[error("File '{path}' has {} invalid rows", .rows.len())]
+    InvalidRows {
+        /// Path of the label file.
+        path: String,
+        /// Every row that failed validation.
+        rows: Vec<YoloFileParseError>,
+    },
+    #[error("Irregular whitespace in file '{}' on line {}", .0.path, display_or_unknown(&.0.row))]
+    IrregularWhitespace(YoloFileParseErrorDetails),
+    #[error("Comment in file '{}' on line {}", .0.path, display_or_unknown(&.0.row))]
+    Comment(YoloFileParseErrorDetails),
+    #[error("Blank line in file '{}' on line {}", .0.path, display_or_unknown(&.0.row))]
+    BlankLine(YoloFileParseErrorDetails),
 }
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -58,16 +99,49 @@ pub struct YoloFileParseErrorDetails {
     /// Column name associated with the error.
     pub column: Option<String>,
     /// The offending numeric value if available.
+    #[serde(default, with = "non_finite")]
     pub value: Option<f32>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
-/// Class definition used when validating label files.
-pub struct YoloClass {
-    /// Numeric class identifier.
-    pub id: isize,
-    /// Human readable class name.
-    pub name: String,
+/// Text of an optional error detail, or `unknown` when it is missing.
+fn display_or_unknown<T: std::fmt::Display>(value: &Option<T>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "unknown".to_string(),
+    }
+}
+
+/// Serializes non-finite values, which JSON has no numbers for, as strings
+/// so they survive a round trip through the label cache.
+mod non_finite {
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Number(f32),
+        Text(String),
+    }
+
+    pub(super) fn serialize<S: Serializer>(
+        value: &Option<f32>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) if !value.is_finite() => serializer.serialize_some(&value.to_string()),
+            _ => value.serialize(serializer),
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<f32>, D::Error> {
+        match Option::<Value>::deserialize(deserializer)? {
+            Some(Value::Number(value)) => Ok(Some(value)),
+            Some(Value::Text(text)) => text.parse().map(Some).map_err(de::Error::custom),
+            None => Ok(None),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -85,6 +159,58 @@ pub struct YoloEntry {
     pub height: f32,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// A single segmentation polygon in a YOLO file.
+pub struct YoloPolygon {
+    /// Class identifier.
+    pub class: isize,
+    /// Normalized `(x, y)` vertices of the polygon.
+    pub points: Vec<(f32, f32)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// A single oriented bounding box in a YOLO-OBB file.
+pub struct YoloOrientedBox {
+    /// Class identifier.
+    pub class: isize,
+    /// Normalized `(x, y)` corners in the order they appear in the file.
+    pub corners: [(f32, f32); 4],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// A single keypoint of a pose entry.
+pub struct YoloKeypoint {
+    /// Normalized x coordinate of the keypoint.
+    pub x: f32,
+    /// Normalized y coordinate of the keypoint.
+    pub y: f32,
+    /// Visibility flag (0 = not labeled, 1 = occluded, 2 = visible) if present.
+    pub visibility: Option<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// A bounding box with keypoints used by pose estimation labels.
+pub struct YoloPose {
+    /// Bounding box surrounding the keypoints.
+    pub entry: YoloEntry,
+    /// Keypoints in the order defined by the project configuration.
+    pub keypoints: Vec<YoloKeypoint>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+/// Annotation format a [`YoloFile`] was read from.
+pub enum LabelSource {
+    /// A YOLO `.txt` label file that can be copied as is.
+    #[default]
+    Yolo,
+    /// Converted from a COCO `instances.json` file.
+    Coco,
+    /// Converted from a Pascal VOC XML file.
+    Voc,
+    /// The image has no label file and is kept as a background image.
+    Missing,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 /// Representation of a `.txt` label file in YOLO format.
 pub struct YoloFile {
@@ -94,11 +220,135 @@ pub struct YoloFile {
     pub path: String,
     /// Parsed label entries.
     pub entries: Vec<YoloEntry>,
+    /// Parsed segmentation polygons.
+    #[serde(default)]
+    pub polygons: Vec<YoloPolygon>,
+    /// Parsed pose entries with keypoints.
+    #[serde(default)]
+    pub poses: Vec<YoloPose>,
+    /// Parsed oriented bounding boxes.
+    #[serde(default)]
+    pub oriented_boxes: Vec<YoloOrientedBox>,
+    /// Format the labels were read from.
+    #[serde(default)]
+    pub source: LabelSource,
+    /// Rows dropped under [`RowErrorPolicy::DropRows`].
+    #[serde(default)]
+    pub warnings: Vec<YoloFileParseError>,
+    /// Rows that deviate from single-space separated columns: irregular
+    /// whitespace, comments and blank lines. They are parsed either way
+    /// and reported as warnings with `strict_formatting`.
+    #[serde(default)]
+    pub formatting: Vec<YoloFileParseError>,
+}
+
+/// Ways a row deviates from single-space separated columns.
+enum Formatting {
+    IrregularWhitespace,
+    Comment,
+    BlankLine,
+}
+
+/// Split a row into its columns.
+///
+/// Columns may be separated by any run of whitespace and everything
+/// after a `#` is a comment. Deviations from single-space separated
+/// columns are returned so strict parsing can report them.
+fn tokenize(line: &str) -> (Vec<&str>, Vec<Formatting>) {
+    let (content, has_comment) = match line.split_once('#') {
+        Some((content, _)) => (content.trim_end(), true),
+        None => (line, false),
+    };
+
+    let parts = content.split_whitespace().collect::<Vec<&str>>();
+    let mut formatting = Vec::<Formatting>::new();
+
+    if has_comment {
+        formatting.push(Formatting::Comment);
+    }
+
+    if parts.is_empty() {
+        if !has_comment {
+            formatting.push(Formatting::BlankLine);
+        }
+    } else if parts.join(" ") != content {
+        formatting.push(Formatting::IrregularWhitespace);
+    }
+
+    (parts, formatting)
+}
+
+#[derive(Default)]
+/// Labels collected while parsing the rows of a file, with the row
+/// numbers used to report duplicates.
+struct ParsedRows {
+    entries: Vec<YoloEntry>,
+    polygons: Vec<YoloPolygon>,
+    poses: Vec<YoloPose>,
+    oriented_boxes: Vec<YoloOrientedBox>,
+    seen_entries: Vec<(usize, YoloEntry)>,
+    seen_polygons: Vec<usize>,
+    seen_oriented_boxes: Vec<usize>,
 }
 
 impl YoloFile {
     /// Read and validate a label file.
+    ///
+    /// Invalid rows are handled according to `metadata.row_errors`: by
+    /// default the first one is returned, with
+    /// [`RowErrorPolicy::ReportAll`] every invalid row is returned in a
+    /// single [`YoloFileParseError::InvalidRows`] and with
+    /// [`RowErrorPolicy::DropRows`] they are moved to `warnings`.
     pub fn new(metadata: &FileMetadata, path: &String) -> Result<YoloFile, YoloFileParseError> {
+        let stop_at_first_error = metadata.row_errors == RowErrorPolicy::Reject;
+        let (yolo_file, row_errors) = Self::parse(metadata, path, stop_at_first_error)?;
+
+        Self::apply_row_errors(metadata, path, yolo_file, row_errors)
+    }
+
+    /// Accept or reject a parsed file according to `metadata.row_errors`.
+    pub(crate) fn apply_row_errors(
+        metadata: &FileMetadata,
+        path: &str,
+        mut yolo_file: YoloFile,
+        mut row_errors: Vec<YoloFileParseError>,
+    ) -> Result<YoloFile, YoloFileParseError> {
+        if row_errors.is_empty() {
+            return Ok(yolo_file);
+        }
+
+        match metadata.row_errors {
+            RowErrorPolicy::Reject => Err(row_errors.remove(0)),
+            RowErrorPolicy::DropRows if !yolo_file.classes().is_empty() => {
+                yolo_file.warnings.extend(row_errors);
+                Ok(yolo_file)
+            }
+            RowErrorPolicy::ReportAll | RowErrorPolicy::DropRows => {
+                Err(YoloFileParseError::InvalidRows {
+                    path: path.to_string(),
+                    rows: row_errors,
+                })
+            }
+        }
+    }
+
+    /// Read a label file, collecting the error of every invalid row.
+    ///
+    /// Returns the labels of all valid rows together with one error per
+    /// invalid row, in row order. Only problems with the file as a whole,
+    /// such as an unreadable or empty file, are returned as an error.
+    pub fn parse_rows(
+        metadata: &FileMetadata,
+        path: &String,
+    ) -> Result<(YoloFile, Vec<YoloFileParseError>), YoloFileParseError> {
+        Self::parse(metadata, path, false)
+    }
+
+    fn parse(
+        metadata: &FileMetadata,
+        path: &String,
+        stop_at_first_error: bool,
+    ) -> Result<(YoloFile, Vec<YoloFileParseError>), YoloFileParseError> {
         let file = read_to_string(path).map_err(|_| {
             YoloFileParseError::FailedToReadFile(YoloFileParseErrorDetails {
                 path: path.to_string(),
@@ -110,224 +360,729 @@ impl YoloFile {
             })
         })?;
 
-        if file.is_empty() {
-            let details = YoloFileParseErrorDetails {
+        let mut rows = ParsedRows::default();
+        let mut row_errors = Vec::<YoloFileParseError>::new();
+        let mut formatting_notices = Vec::<YoloFileParseError>::new();
+
+        let expected_columns = match metadata.keypoints {
+            Some(ref keypoints) => Some(5 + keypoints.count * keypoints.dimensions()),
+            None if metadata.oriented_bounding_boxes => Some(9),
+            None => None,
+        };
+
+        // `str::lines` would also strip the `\r` of CRLF line endings, which
+        // are reported as irregular whitespace, so only `\n` is removed.
+        for (index, line) in file.split_inclusive('\n').enumerate() {
+            let line = line.strip_suffix('\n').unwrap_or(line);
+            let (parts, formatting) = tokenize(line);
+
+            formatting_notices.extend(formatting.into_iter().map(|formatting| {
+                let details = YoloFileParseErrorDetails {
+                    path: path.to_string(),
+                    class: None,
+                    row: Some(index),
+                    other_row: None,
+                    column: None,
+                    value: None,
+                };
+
+                match formatting {
+                    Formatting::IrregularWhitespace => {
+                        YoloFileParseError::IrregularWhitespace(details)
+                    }
+                    Formatting::Comment => YoloFileParseError::Comment(details),
+                    Formatting::BlankLine => YoloFileParseError::BlankLine(details),
+                }
+            }));
+
+            if parts.is_empty() {
+                continue;
+            }
+
+            if let Err(error) =
+                Self::parse_row(metadata, path, index, &parts, expected_columns, &mut rows)
+            {
+                row_errors.push(error);
+
+                if stop_at_first_error {
+                    break;
+                }
+            }
+        }
+
+        let stem = get_file_stem(Path::new(path))
+            .map_err(|_| {
+                YoloFileParseError::FailedToGetFileStem(YoloFileParseErrorDetails {
+                    path: path.to_string(),
+                    class: None,
+                    row: None,
+                    other_row: None,
+                    column: None,
+                    value: None,
+                })
+            })?
+            .to_string();
+
+        let yolo_file = YoloFile {
+            stem,
+            path: path.to_string(),
+            entries: rows.entries,
+            polygons: rows.polygons,
+            poses: rows.poses,
+            oriented_boxes: rows.oriented_boxes,
+            source: LabelSource::Yolo,
+            warnings: vec![],
+            formatting: formatting_notices,
+        };
+
+        if yolo_file.is_background()
+            && row_errors.is_empty()
+            && metadata.background == BackgroundPolicy::Reject
+        {
+            return Err(YoloFileParseError::EmptyFile(YoloFileParseErrorDetails {
                 path: path.to_string(),
                 class: None,
                 row: None,
                 other_row: None,
                 column: None,
                 value: None,
-            };
-
-            return Err(YoloFileParseError::EmptyFile(details));
+            }));
         }
 
-        let mut entries = Vec::<YoloEntry>::new();
+        Ok((yolo_file, row_errors))
+    }
 
+    /// Validate a single row and add its labels to `rows`.
+    fn parse_row(
+        metadata: &FileMetadata,
+        path: &str,
+        index: usize,
+        parts: &[&str],
+        expected_columns: Option<usize>,
+        rows: &mut ParsedRows,
+    ) -> Result<(), YoloFileParseError> {
         let tolerance = metadata.duplicate_tolerance;
-        let mut seen_bboxes: Vec<(f32, f32, f32, f32)> = Vec::new();
 
-        for (index, line) in file.lines().enumerate() {
-            let parts: Vec<&str> = line.split(" ").collect();
+        let is_malformed = match expected_columns {
+            Some(columns) => parts.len() != columns,
+            None => parts.len() < 5,
+        };
 
-            if parts.len() != 5 {
-                return Err(YoloFileParseError::InvalidFormat(
-                    YoloFileParseErrorDetails {
-                        path: path.to_string(),
-                        class: None,
-                        row: None,
-                        other_row: None,
-                        column: None,
-                        value: None,
-                    },
-                ));
-            }
+        if is_malformed {
+            return Err(YoloFileParseError::InvalidFormat(
+                YoloFileParseErrorDetails {
+                    path: path.to_string(),
+                    class: None,
+                    row: Some(index),
+                    other_row: None,
+                    column: None,
+                    value: None,
+                },
+            ));
+        }
 
-            let class = parts[0].parse::<isize>().map_err(|_| {
-                YoloFileParseError::FailedToParseClassId(YoloFileParseErrorDetails {
+        let class = parts[0].parse::<isize>().map_err(|_| {
+            YoloFileParseError::FailedToParseClassId(YoloFileParseErrorDetails {
+                path: path.to_string(),
+                class: Some(parts[0].to_string()),
+                row: Some(index),
+                other_row: None,
+                column: Some("class".to_string()),
+                value: None,
+            })
+        })?;
+
+        let found = metadata.classes.iter().any(|c| c.id == class);
+        if !found {
+            return Err(YoloFileParseError::ClassIdNotFound(
+                YoloFileParseErrorDetails {
                     path: path.to_string(),
-                    class: Some(parts[0].to_string()),
+                    class: Some(class.to_string()),
                     row: Some(index),
                     other_row: None,
                     column: Some("class".to_string()),
                     value: None,
-                })
-            })?;
+                },
+            ));
+        }
 
-            let found = metadata.classes.iter().any(|c| c.id == class);
-            if !found {
-                return Err(YoloFileParseError::ClassIdNotFound(
-                    YoloFileParseErrorDetails {
+        if metadata.oriented_bounding_boxes && metadata.keypoints.is_none() {
+            let oriented_box = Self::parse_oriented_box(path, index, class, &parts[1..])?;
+
+            if tolerance > 0.0 {
+                for (prev_index, prev_box) in rows
+                    .seen_oriented_boxes
+                    .iter()
+                    .zip(rows.oriented_boxes.iter())
+                {
+                    if prev_box.is_duplicate_of(&oriented_box, tolerance) {
+                        return Err(YoloFileParseError::DuplicateEntries(
+                            YoloFileParseErrorDetails {
+                                path: path.to_string(),
+                                class: None,
+                                row: Some(*prev_index),
+                                other_row: Some(index),
+                                column: None,
+                                value: None,
+                            },
+                        ));
+                    }
+                }
+            }
+
+            rows.seen_oriented_boxes.push(index);
+            rows.oriented_boxes.push(oriented_box);
+            return Ok(());
+        }
+
+        if expected_columns.is_none() && parts.len() > 5 {
+            let polygon = Self::parse_polygon(path, index, class, &parts[1..])?;
+
+            if tolerance > 0.0 {
+                for (prev_index, prev_polygon) in
+                    rows.seen_polygons.iter().zip(rows.polygons.iter())
+                {
+                    if prev_polygon.is_duplicate_of(&polygon, tolerance) {
+                        return Err(YoloFileParseError::DuplicateEntries(
+                            YoloFileParseErrorDetails {
+                                path: path.to_string(),
+                                class: None,
+                                row: Some(*prev_index),
+                                other_row: Some(index),
+                                column: None,
+                                value: None,
+                            },
+                        ));
+                    }
+                }
+            }
+
+            rows.seen_polygons.push(index);
+            rows.polygons.push(polygon);
+            return Ok(());
+        }
+
+        let entry = Self::parse_entry(path, index, class, &parts[1..5])?;
+
+        let keypoints = match metadata.keypoints {
+            Some(ref layout) => Some(Self::parse_keypoints(
+                path,
+                index,
+                class,
+                layout,
+                &parts[5..],
+            )?),
+            None => None,
+        };
+
+        if tolerance > 0.0 {
+            for (prev_index, prev_entry) in rows.seen_entries.iter() {
+                if prev_entry.is_duplicate_of(&entry, tolerance) {
+                    return Err(YoloFileParseError::DuplicateEntries(
+                        YoloFileParseErrorDetails {
+                            path: path.to_string(),
+                            class: None,
+                            row: Some(*prev_index),
+                            other_row: Some(index),
+                            column: None,
+                            value: None,
+                        },
+                    ));
+                }
+            }
+        }
+
+        rows.seen_entries.push((index, entry.clone()));
+
+        match keypoints {
+            Some(keypoints) => rows.poses.push(YoloPose { entry, keypoints }),
+            None => rows.entries.push(entry),
+        }
+
+        Ok(())
+    }
+
+    /// Axis-aligned bounding box of every labelled instance.
+    ///
+    /// Polygons and oriented boxes are replaced by the smallest box
+    /// holding all their points. Boxes follow the order of
+    /// [`YoloFile::classes`].
+    pub fn bounding_boxes(&self) -> Vec<YoloEntry> {
+        self.entries
+            .iter()
+            .cloned()
+            .chain(
+                self.polygons
+                    .iter()
+                    .map(|polygon| YoloEntry::enclosing(polygon.class, &polygon.points)),
+            )
+            .chain(self.poses.iter().map(|pose| pose.entry.clone()))
+            .chain(self.oriented_boxes.iter().map(|oriented_box| {
+                YoloEntry::enclosing(oriented_box.class, &oriented_box.corners)
+            }))
+            .collect()
+    }
+
+    /// Empty label for an image without a label file.
+    pub fn background(stem: &str) -> YoloFile {
+        YoloFile {
+            stem: stem.to_string(),
+            path: String::new(),
+            entries: vec![],
+            polygons: vec![],
+            poses: vec![],
+            oriented_boxes: vec![],
+            source: LabelSource::Missing,
+            warnings: vec![],
+            formatting: vec![],
+        }
+    }
+
+    /// Whether the file labels no objects.
+    pub fn is_background(&self) -> bool {
+        self.classes().is_empty()
+    }
+
+    /// Class of every labelled instance in the file.
+    pub fn classes(&self) -> Vec<isize> {
+        self.entries
+            .iter()
+            .map(|entry| entry.class)
+            .chain(self.polygons.iter().map(|polygon| polygon.class))
+            .chain(self.poses.iter().map(|pose| pose.entry.class))
+            .chain(
+                self.oriented_boxes
+                    .iter()
+                    .map(|oriented_box| oriented_box.class),
+            )
+            .collect()
+    }
+
+    /// Render the parsed labels as the contents of a YOLO `.txt` file.
+    ///
+    /// Rows are written as bounding boxes, polygons, poses and oriented
+    /// boxes, in that order.
+    pub fn to_yolo_string(&self) -> String {
+        let mut rows = Vec::<String>::new();
+
+        for entry in &self.entries {
+            rows.push(format!(
+                "{} {} {} {} {}",
+                entry.class, entry.x_center, entry.y_center, entry.width, entry.height
+            ));
+        }
+
+        for polygon in &self.polygons {
+            let points = polygon
+                .points
+                .iter()
+                .map(|(x, y)| format!("{} {}", x, y))
+                .collect::<Vec<String>>()
+                .join(" ");
+            rows.push(format!("{} {}", polygon.class, points));
+        }
+
+        for pose in &self.poses {
+            let mut row = format!(
+                "{} {} {} {} {}",
+                pose.entry.class,
+                pose.entry.x_center,
+                pose.entry.y_center,
+                pose.entry.width,
+                pose.entry.height
+            );
+
+            for keypoint in &pose.keypoints {
+                row.push_str(&format!(" {} {}", keypoint.x, keypoint.y));
+                if let Some(visibility) = keypoint.visibility {
+                    row.push_str(&format!(" {}", visibility));
+                }
+            }
+
+            rows.push(row);
+        }
+
+        for oriented_box in &self.oriented_boxes {
+            let corners = oriented_box
+                .corners
+                .iter()
+                .map(|(x, y)| format!("{} {}", x, y))
+                .collect::<Vec<String>>()
+                .join(" ");
+            rows.push(format!("{} {}", oriented_box.class, corners));
+        }
+
+        rows.join("\n")
+    }
+
+    /// Parse and validate the four bounding box columns of a row.
+    fn parse_entry(
+        path: &str,
+        index: usize,
+        class: isize,
+        columns: &[&str],
+    ) -> Result<YoloEntry, YoloFileParseError> {
+        let x_center = columns[0].parse::<f32>().map_err(|_| {
+            YoloFileParseError::FailedToParseColumn(YoloFileParseErrorDetails {
+                path: path.to_string(),
+                class: Some(class.to_string()),
+                row: Some(index),
+                other_row: None,
+                column: Some("x".to_string()),
+                value: None,
+            })
+        })?;
+
+        let y_center = columns[1].parse::<f32>().map_err(|_| {
+            YoloFileParseError::FailedToParseColumn(YoloFileParseErrorDetails {
+                path: path.to_string(),
+                class: Some(class.to_string()),
+                row: Some(index),
+                other_row: None,
+                column: Some("y".to_string()),
+                value: None,
+            })
+        })?;
+
+        let width = columns[2].parse::<f32>().map_err(|_| {
+            YoloFileParseError::FailedToParseColumn(YoloFileParseErrorDetails {
+                path: path.to_string(),
+                class: Some(class.to_string()),
+                row: Some(index),
+                other_row: None,
+                column: Some("w".to_string()),
+                value: None,
+            })
+        })?;
+
+        let height = columns[3].parse::<f32>().map_err(|_| {
+            YoloFileParseError::FailedToParseColumn(YoloFileParseErrorDetails {
+                path: path.to_string(),
+                class: Some(class.to_string()),
+                row: Some(index),
+                other_row: None,
+                column: Some("h".to_string()),
+                value: None,
+            })
+        })?;
+
+        // if !(0..=79).contains(&class) {
+        //     return Err(Box::new(YoloFileParseError::ClassIdGreaterThanMax(
+        //         path.to_string(),
+        //         class,
+        //     )));
+        // }
+
+        if !(0.0..=1.0).contains(&x_center) {
+            return Err(YoloFileParseError::LabelDataOutOfRange(
+                YoloFileParseErrorDetails {
+                    path: path.to_string(),
+                    class: Some(class.to_string()),
+                    row: Some(index),
+                    other_row: None,
+                    column: Some("x".to_string()),
+                    value: Some(x_center),
+                },
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&y_center) {
+            return Err(YoloFileParseError::LabelDataOutOfRange(
+                YoloFileParseErrorDetails {
+                    path: path.to_string(),
+                    class: Some(class.to_string()),
+                    row: Some(index),
+                    other_row: None,
+                    column: Some("y".to_string()),
+                    value: Some(y_center),
+                },
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&width) {
+            return Err(YoloFileParseError::LabelDataOutOfRange(
+                YoloFileParseErrorDetails {
+                    path: path.to_string(),
+                    class: Some(class.to_string()),
+                    row: Some(index),
+                    other_row: None,
+                    column: Some("w".to_string()),
+                    value: Some(width),
+                },
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&height) {
+            return Err(YoloFileParseError::LabelDataOutOfRange(
+                YoloFileParseErrorDetails {
+                    path: path.to_string(),
+                    class: Some(class.to_string()),
+                    row: Some(index),
+                    other_row: None,
+                    column: Some("h".to_string()),
+                    value: Some(height),
+                },
+            ));
+        }
+
+        Ok(YoloEntry {
+            class,
+            x_center,
+            y_center,
+            width,
+            height,
+        })
+    }
+
+    /// Parse and validate the keypoint columns of a pose row.
+    fn parse_keypoints(
+        path: &str,
+        index: usize,
+        class: isize,
+        layout: &Keypoints,
+        columns: &[&str],
+    ) -> Result<Vec<YoloKeypoint>, YoloFileParseError> {
+        let mut keypoints = Vec::<YoloKeypoint>::with_capacity(layout.count);
+
+        for (position, keypoint) in columns.chunks(layout.dimensions()).enumerate() {
+            let mut values = Vec::<f32>::with_capacity(keypoint.len());
+
+            for (axis, raw) in ["px", "py", "v"].iter().zip(keypoint.iter()) {
+                let column = format!("{}{}", axis, position + 1);
+
+                let value = raw.parse::<f32>().map_err(|_| {
+                    YoloFileParseError::FailedToParseColumn(YoloFileParseErrorDetails {
                         path: path.to_string(),
                         class: Some(class.to_string()),
                         row: Some(index),
                         other_row: None,
-                        column: Some("class".to_string()),
+                        column: Some(column.clone()),
                         value: None,
-                    },
-                ));
+                    })
+                })?;
+
+                let in_range = match *axis {
+                    "v" => [0.0, 1.0, 2.0].contains(&value),
+                    _ => (0.0..=1.0).contains(&value),
+                };
+
+                if !in_range {
+                    return Err(YoloFileParseError::LabelDataOutOfRange(
+                        YoloFileParseErrorDetails {
+                            path: path.to_string(),
+                            class: Some(class.to_string()),
+                            row: Some(index),
+                            other_row: None,
+                            column: Some(column),
+                            value: Some(value),
+                        },
+                    ));
+                }
+
+                values.push(value);
             }
 
-            let x_center = parts[1].parse::<f32>().map_err(|_| {
-                YoloFileParseError::FailedToParseColumn(YoloFileParseErrorDetails {
+            keypoints.push(YoloKeypoint {
+                x: values[0],
+                y: values[1],
+                visibility: values.get(2).map(|v| *v as u8),
+            });
+        }
+
+        Ok(keypoints)
+    }
+
+    /// Parse and validate the coordinates of a segmentation polygon row.
+    fn parse_polygon(
+        path: &str,
+        index: usize,
+        class: isize,
+        coordinates: &[&str],
+    ) -> Result<YoloPolygon, YoloFileParseError> {
+        if coordinates.len() % 2 == 1 {
+            return Err(YoloFileParseError::OddPolygonCoordinates(
+                YoloFileParseErrorDetails {
                     path: path.to_string(),
                     class: Some(class.to_string()),
                     row: Some(index),
                     other_row: None,
-                    column: Some("x".to_string()),
+                    column: None,
                     value: None,
-                })
-            })?;
+                },
+            ));
+        }
 
-            let y_center = parts[2].parse::<f32>().map_err(|_| {
-                YoloFileParseError::FailedToParseColumn(YoloFileParseErrorDetails {
+        if coordinates.len() < 6 {
+            return Err(YoloFileParseError::TooFewPolygonPoints(
+                YoloFileParseErrorDetails {
                     path: path.to_string(),
                     class: Some(class.to_string()),
                     row: Some(index),
                     other_row: None,
-                    column: Some("y".to_string()),
+                    column: None,
                     value: None,
-                })
-            })?;
+                },
+            ));
+        }
 
-            let width = parts[3].parse::<f32>().map_err(|_| {
-                YoloFileParseError::FailedToParseColumn(YoloFileParseErrorDetails {
+        let points = Self::parse_points(path, index, class, coordinates)?;
+
+        Ok(YoloPolygon { class, points })
+    }
+
+    /// Parse and validate the corners of an oriented bounding box row.
+    fn parse_oriented_box(
+        path: &str,
+        index: usize,
+        class: isize,
+        coordinates: &[&str],
+    ) -> Result<YoloOrientedBox, YoloFileParseError> {
+        let points = Self::parse_points(path, index, class, coordinates)?;
+        let corners = [points[0], points[1], points[2], points[3]];
+
+        let turns = geometry::turn_directions(&corners);
+        let left_turns = turns.iter().filter(|turn| **turn > 0.0).count();
+        let right_turns = turns.iter().filter(|turn| **turn < 0.0).count();
+
+        if left_turns == 2 && right_turns == 2 {
+            return Err(YoloFileParseError::InvalidCornerOrder(
+                YoloFileParseErrorDetails {
                     path: path.to_string(),
                     class: Some(class.to_string()),
                     row: Some(index),
                     other_row: None,
-                    column: Some("w".to_string()),
+                    column: None,
                     value: None,
-                })
-            })?;
+                },
+            ));
+        }
+
+        if left_turns != 4 && right_turns != 4 {
+            return Err(YoloFileParseError::NonConvexBox(
+                YoloFileParseErrorDetails {
+                    path: path.to_string(),
+                    class: Some(class.to_string()),
+                    row: Some(index),
+                    other_row: None,
+                    column: None,
+                    value: None,
+                },
+            ));
+        }
+
+        Ok(YoloOrientedBox { class, corners })
+    }
 
-            let height = parts[4].parse::<f32>().map_err(|_| {
+    /// Parse normalized `x y` coordinate pairs, naming columns `x1`, `y1`, ...
+    fn parse_points(
+        path: &str,
+        index: usize,
+        class: isize,
+        coordinates: &[&str],
+    ) -> Result<Vec<(f32, f32)>, YoloFileParseError> {
+        let mut values = Vec::<f32>::with_capacity(coordinates.len());
+
+        for (position, raw) in coordinates.iter().enumerate() {
+            let axis = if position % 2 == 0 { "x" } else { "y" };
+            let column = format!("{}{}", axis, position / 2 + 1);
+
+            let value = raw.parse::<f32>().map_err(|_| {
                 YoloFileParseError::FailedToParseColumn(YoloFileParseErrorDetails {
                     path: path.to_string(),
                     class: Some(class.to_string()),
                     row: Some(index),
                     other_row: None,
-                    column: Some("h".to_string()),
+                    column: Some(column.clone()),
                     value: None,
                 })
             })?;
 
-            // if !(0..=79).contains(&class) {
-            //     return Err(Box::new(YoloFileParseError::ClassIdGreaterThanMax(
-            //         path.to_string(),
-            //         class,
-            //     )));
-            // }
-
-            if !(0.0..=1.0).contains(&x_center) {
+            if !(0.0..=1.0).contains(&value) {
                 return Err(YoloFileParseError::LabelDataOutOfRange(
                     YoloFileParseErrorDetails {
                         path: path.to_string(),
                         class: Some(class.to_string()),
                         row: Some(index),
                         other_row: None,
-                        column: Some("x".to_string()),
-                        value: Some(x_center),
+                        column: Some(column),
+                        value: Some(value),
                     },
                 ));
             }
 
-            if !(0.0..=1.0).contains(&y_center) {
-                return Err(YoloFileParseError::LabelDataOutOfRange(
-                    YoloFileParseErrorDetails {
-                        path: path.to_string(),
-                        class: Some(class.to_string()),
-                        row: Some(index),
-                        other_row: None,
-                        column: Some("y".to_string()),
-                        value: Some(y_center),
-                    },
-                ));
-            }
+            values.push(value);
+        }
 
-            if !(0.0..=1.0).contains(&width) {
-                return Err(YoloFileParseError::LabelDataOutOfRange(
-                    YoloFileParseErrorDetails {
-                        path: path.to_string(),
-                        class: Some(class.to_string()),
-                        row: Some(index),
-                        other_row: None,
-                        column: Some("w".to_string()),
-                        value: Some(width),
-                    },
-                ));
-            }
+        let points = values
+            .chunks(2)
+            .map(|point| (point[0], point[1]))
+            .collect::<Vec<(f32, f32)>>();
 
-            if !(0.0..=1.0).contains(&height) {
-                return Err(YoloFileParseError::LabelDataOutOfRange(
-                    YoloFileParseErrorDetails {
-                        path: path.to_string(),
-                        class: Some(class.to_string()),
-                        row: Some(index),
-                        other_row: None,
-                        column: Some("h".to_string()),
-                        value: Some(height),
-                    },
-                ));
-            }
+        Ok(points)
+    }
+}
 
-            let bbox = (
-                x_center - width / 2.0,
-                x_center + width / 2.0,
-                y_center - height / 2.0,
-                y_center + height / 2.0,
-            );
+impl YoloEntry {
+    /// Smallest box holding every point.
+    pub fn enclosing(class: isize, points: &[(f32, f32)]) -> YoloEntry {
+        let (mut left, mut right) = (f32::MAX, f32::MIN);
+        let (mut top, mut bottom) = (f32::MAX, f32::MIN);
 
-            if tolerance > 0.0 {
-                for (prev_index, (px1, px2, py1, py2)) in seen_bboxes.iter().enumerate() {
-                    if (bbox.0 - *px1).abs() <= tolerance
-                        && (bbox.1 - *px2).abs() <= tolerance
-                        && (bbox.2 - *py1).abs() <= tolerance
-                        && (bbox.3 - *py2).abs() <= tolerance
-                    {
-                        return Err(YoloFileParseError::DuplicateEntries(
-                            YoloFileParseErrorDetails {
-                                path: path.to_string(),
-                                class: None,
-                                row: Some(prev_index),
-                                other_row: Some(index),
-                                column: None,
-                                value: None,
-                            },
-                        ));
-                    }
-                }
-            }
-
-            seen_bboxes.push(bbox);
+        for &(x, y) in points {
+            left = left.min(x);
+            right = right.max(x);
+            top = top.min(y);
+            bottom = bottom.max(y);
+        }
 
-            entries.push(YoloEntry {
-                class,
-                x_center,
-                y_center,
-                width,
-                height,
-            });
+        YoloEntry {
+            class,
+            x_center: (left + right) / 2.0,
+            y_center: (top + bottom) / 2.0,
+            width: right - left,
+            height: bottom - top,
         }
+    }
 
-        let stem = get_file_stem(Path::new(path))
-            .map_err(|_| {
-                YoloFileParseError::FailedToGetFileStem(YoloFileParseErrorDetails {
-                    path: path.to_string(),
-                    class: None,
-                    row: None,
-                    other_row: None,
-                    column: None,
-                    value: None,
+    /// Edges of the box as `(left, right, top, bottom)`.
+    pub fn bounds(&self) -> (f32, f32, f32, f32) {
+        (
+            self.x_center - self.width / 2.0,
+            self.x_center + self.width / 2.0,
+            self.y_center - self.height / 2.0,
+            self.y_center + self.height / 2.0,
+        )
+    }
+
+    /// Whether every edge of `other` is within `tolerance` of this box.
+    pub fn is_duplicate_of(&self, other: &YoloEntry, tolerance: f32) -> bool {
+        let (x1, x2, y1, y2) = self.bounds();
+        let (ox1, ox2, oy1, oy2) = other.bounds();
+
+        (x1 - ox1).abs() <= tolerance
+            && (x2 - ox2).abs() <= tolerance
+            && (y1 - oy1).abs() <= tolerance
+            && (y2 - oy2).abs() <= tolerance
+    }
+}
+
+impl YoloPolygon {
+    /// Whether `other` has the same vertices as this polygon within `tolerance`.
+    pub fn is_duplicate_of(&self, other: &YoloPolygon, tolerance: f32) -> bool {
+        self.points.len() == other.points.len()
+            && self
+                .points
+                .iter()
+                .zip(other.points.iter())
+                .all(|((x1, y1), (x2, y2))| {
+                    (x1 - x2).abs() <= tolerance && (y1 - y2).abs() <= tolerance
                 })
-            })?
-            .to_string();
+    }
+}
 
-        Ok(YoloFile {
-            stem,
-            path: path.to_string(),
-            entries,
-        })
+impl YoloOrientedBox {
+    /// Whether `other` overlaps this box with an IoU of at least `1.0 - tolerance`.
+    pub fn is_duplicate_of(&self, other: &YoloOrientedBox, tolerance: f32) -> bool {
+        geometry::convex_iou(&self.corners, &other.corners) >= 1.0 - tolerance
     }
 }
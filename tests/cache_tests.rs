@@ -0,0 +1,192 @@
+mod common;
+
+#[cfg(test)]
+mod cache_tests {
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+        time::{Duration, SystemTime},
+    };
+
+    use rstest::rstest;
+    use yolo_io::{PairingError, YoloDataQualityReport, YoloProject, YoloProjectConfig};
+
+    use crate::common::{create_dir_and_write_file, create_yolo_project_config, TEST_SANDBOX_DIR};
+
+    /// Replace the contents of a label file without changing its size or
+    /// modification time.
+    fn rewrite_preserving_stamp(path: &Path, contents: &str) {
+        let modified = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .expect("Unable to read modification time");
+
+        fs::write(path, contents).expect("Unable to write label");
+        set_modified(path, modified);
+    }
+
+    fn set_modified(path: &Path, modified: SystemTime) {
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .and_then(|file| file.set_modified(modified))
+            .expect("Unable to set modification time");
+    }
+
+    fn has_class_error(project: &YoloProject) -> bool {
+        project
+            .get_invalid_pairs()
+            .iter()
+            .any(|error| matches!(error, PairingError::LabelFileError(_)))
+    }
+
+    fn setup(config: &mut YoloProjectConfig, name: &str) -> PathBuf {
+        let this_test_directory = format!("{}/{}", TEST_SANDBOX_DIR, name);
+        let cache_path = PathBuf::from(format!("{}_cache/labels.json", this_test_directory));
+        let _ = fs::remove_dir_all(&this_test_directory);
+        let _ = fs::remove_file(&cache_path);
+
+        create_dir_and_write_file(
+            &PathBuf::from(format!("{}/cached.jpg", this_test_directory)),
+            "",
+        );
+        create_dir_and_write_file(
+            &PathBuf::from(format!("{}/cached.txt", this_test_directory)),
+            "0 0.5 0.5 0.5 0.5",
+        );
+
+        config.source_paths.images = this_test_directory.clone();
+        config.source_paths.labels = this_test_directory.clone();
+        config.source_paths.cache = Some(cache_path.clone());
+
+        PathBuf::from(format!("{}/cached.txt", this_test_directory))
+    }
+
+    #[rstest]
+    fn test_cache_reuses_unchanged_labels(mut create_yolo_project_config: YoloProjectConfig) {
+        let label_path = setup(&mut create_yolo_project_config, "cache_reuse");
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+        assert_eq!(project.get_valid_pairs().len(), 1);
+        assert!(create_yolo_project_config
+            .source_paths
+            .cache
+            .as_ref()
+            .unwrap()
+            .exists());
+
+        // Same size and modification time: the cached parse is used.
+        rewrite_preserving_stamp(&label_path, "7 0.5 0.5 0.5 0.5");
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+        assert_eq!(project.get_valid_pairs().len(), 1);
+
+        // A new modification time makes the file parse again.
+        set_modified(&label_path, SystemTime::now() + Duration::from_secs(60));
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+        assert!(project.get_valid_pairs().is_empty());
+        assert!(has_class_error(&project));
+    }
+
+    #[rstest]
+    fn test_cache_is_invalidated_by_label_settings(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let label_path = setup(&mut create_yolo_project_config, "cache_settings");
+
+        YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        rewrite_preserving_stamp(&label_path, "7 0.5 0.5 0.5 0.5");
+
+        create_yolo_project_config
+            .export
+            .class_map
+            .insert(2, "bicycle".to_string());
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+        assert!(has_class_error(&project));
+
+        // Restoring the label and changing the tolerance reparses again.
+        rewrite_preserving_stamp(&label_path, "0 0.5 0.5 0.5 0.5");
+        create_yolo_project_config.export.duplicate_tolerance = 0.5;
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+        assert_eq!(project.get_valid_pairs().len(), 1);
+    }
+
+    #[rstest]
+    fn test_cache_reuses_pairing_until_files_change(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let label_path = setup(&mut create_yolo_project_config, "cache_pairing");
+        let cache_path = create_yolo_project_config
+            .source_paths
+            .cache
+            .clone()
+            .unwrap();
+
+        let cached_pairs = || {
+            let cache: serde_json::Value = serde_json::from_str(
+                &fs::read_to_string(&cache_path).expect("Unable to read cache"),
+            )
+            .expect("Unable to parse cache");
+            cache["pairs"].as_array().map(Vec::len)
+        };
+
+        YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+        assert_eq!(cached_pairs(), Some(1));
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+        assert_eq!(project.get_valid_pairs().len(), 1);
+        assert!(project.get_invalid_pairs().is_empty());
+
+        // A new image changes the file listing and pairs again.
+        create_dir_and_write_file(&label_path.with_file_name("uncached.jpg"), "");
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+        assert_eq!(project.get_valid_pairs().len(), 1);
+        assert!(project
+            .get_invalid_pairs()
+            .iter()
+            .any(|error| matches!(error, PairingError::LabelFileMissing(_))));
+        assert_eq!(cached_pairs(), Some(1 + project.get_invalid_pairs().len()));
+    }
+
+    #[rstest]
+    fn test_cache_write_failure_does_not_fail_load(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let label_path = setup(&mut create_yolo_project_config, "cache_unwritable");
+
+        // The cache directory would have to be created below a file.
+        create_yolo_project_config.source_paths.cache = Some(label_path.join("labels.json"));
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        assert_eq!(project.get_valid_pairs().len(), 1);
+    }
+
+    #[rstest]
+    fn test_cache_keeps_non_finite_values(mut create_yolo_project_config: YoloProjectConfig) {
+        let label_path = setup(&mut create_yolo_project_config, "cache_non_finite");
+        fs::write(&label_path, "0 inf 0.5 0.2 0.2").expect("Unable to write label");
+
+        let first =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+        let cached =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        assert_eq!(cached.get_invalid_pairs(), first.get_invalid_pairs());
+
+        let report = YoloDataQualityReport::generate(cached).expect("Expected a report");
+        assert!(report.contains("Value is 'inf'"));
+    }
+}
@@ -0,0 +1,571 @@
+mod common;
+
+#[cfg(test)]
+mod coco_tests {
+    use std::{fs, path::PathBuf};
+
+    use image::{ImageBuffer, Rgb};
+    use rstest::rstest;
+    use yolo_io::{
+        BackgroundPolicy, CocoDataset, CocoExporter, CocoImporter, CocoSegmentation, FileMetadata,
+        ImageLabelPair, ImageValidation, ImportError, LabelSource, LinkMode, OverwritePolicy,
+        PairingError, PairingResult, RowErrorPolicy, Split, YoloDataQualityReport, YoloEntry,
+        YoloFile, YoloKeypoint, YoloOrientedBox, YoloPolygon, YoloPose, YoloProject,
+        YoloProjectConfig, YoloProjectExporter,
+    };
+
+    use crate::common::{
+        create_dir_and_write_file, create_image_file, create_yolo_project_config, image_data,
+        TEST_SANDBOX_DIR,
+    };
+
+    const COCO_JSON: &str = r#"{
+        "images": [
+            {"id": 1, "file_name": "first.jpg", "width": 800, "height": 800},
+            {"id": 2, "file_name": "second.jpg", "width": 800, "height": 800},
+            {"id": 3, "file_name": "third.jpg", "width": 800, "height": 800}
+        ],
+        "annotations": [
+            {"id": 10, "image_id": 1, "category_id": 7, "bbox": [200, 200, 400, 200]},
+            {"id": 11, "image_id": 1, "category_id": 3, "bbox": [0, 0, 80, 160]},
+            {"id": 12, "image_id": 2, "category_id": 99, "bbox": [0, 0, 10, 10]},
+            {"id": 13, "image_id": 3, "category_id": 3, "bbox": [700, 700, 200, 200]}
+        ],
+        "categories": [
+            {"id": 7, "name": "car"},
+            {"id": 3, "name": "person"}
+        ]
+    }"#;
+
+    fn create_coco_dataset(
+        name: &str,
+        image_data: &ImageBuffer<Rgb<u8>, Vec<u8>>,
+    ) -> (String, PathBuf) {
+        let this_test_directory = format!("{}/{}", TEST_SANDBOX_DIR, name);
+        let _ = fs::remove_dir_all(&this_test_directory);
+
+        for file_name in ["first.jpg", "second.jpg", "third.jpg"] {
+            let image_file = PathBuf::from(format!("{}/images/{}", this_test_directory, file_name));
+            create_image_file(&image_file, image_data);
+        }
+
+        let annotations = PathBuf::from(format!("{}/instances.json", this_test_directory));
+        create_dir_and_write_file(&annotations, COCO_JSON);
+
+        (format!("{}/images", this_test_directory), annotations)
+    }
+
+    #[rstest]
+    fn test_coco_import_builds_class_map_and_normalized_entries(
+        image_data: ImageBuffer<Rgb<u8>, Vec<u8>>,
+    ) {
+        let (images, annotations) = create_coco_dataset("coco_import", &image_data);
+
+        let metadata = FileMetadata {
+            background: BackgroundPolicy::Reject,
+            ..Default::default()
+        };
+        let import =
+            CocoImporter::import(&annotations, &images, &metadata).expect("Unable to import COCO");
+
+        assert_eq!(import.class_map.get(&0), Some(&"person".to_string()));
+        assert_eq!(import.class_map.get(&1), Some(&"car".to_string()));
+        assert_eq!(import.stems, vec!["first", "second", "third"]);
+
+        let valid = import
+            .pairs
+            .iter()
+            .filter_map(|pair| match pair {
+                yolo_io::PairingResult::Valid(pair) => Some(pair.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(valid.len(), 1);
+        assert_eq!(
+            valid[0].label_file.as_ref().unwrap().entries,
+            vec![
+                YoloEntry {
+                    class: 1,
+                    x_center: 0.5,
+                    y_center: 0.375,
+                    width: 0.5,
+                    height: 0.25,
+                },
+                YoloEntry {
+                    class: 0,
+                    x_center: 0.05,
+                    y_center: 0.1,
+                    width: 0.1,
+                    height: 0.2,
+                },
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_coco_import_reports_unmappable_annotations(
+        image_data: ImageBuffer<Rgb<u8>, Vec<u8>>,
+        create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let (images, annotations) = create_coco_dataset("coco_unmappable", &image_data);
+
+        let mut config = create_yolo_project_config;
+        config.source_paths.images = images;
+
+        let project = YoloProject::from_coco(&config, &annotations).expect("Unable to import");
+
+        let unmappable = project
+            .get_invalid_pairs()
+            .into_iter()
+            .filter_map(|error| match error {
+                PairingError::UnmappableAnnotation(annotation) => Some(annotation),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(unmappable.len(), 2);
+        assert_eq!(unmappable[0].annotation, Some("12".to_string()));
+        assert_eq!(unmappable[0].reason, "unknown category id 99");
+        assert_eq!(unmappable[1].image, Some("third.jpg".to_string()));
+        assert!(project.get_pair("second").is_none());
+        assert!(project.get_pair("third").is_none());
+
+        let report = YoloDataQualityReport::generate(project).expect("Expected a report");
+        assert!(report.contains("\"source\":\"UnmappableAnnotation\""));
+    }
+
+    #[rstest]
+    #[case::reject(BackgroundPolicy::Reject, 1)]
+    #[case::empty_labels(BackgroundPolicy::EmptyLabels, 3)]
+    fn test_coco_import_applies_background_policy(
+        image_data: ImageBuffer<Rgb<u8>, Vec<u8>>,
+        #[case] background: BackgroundPolicy,
+        #[case] expected_valid: usize,
+    ) {
+        let (images, _) =
+            create_coco_dataset(&format!("coco_background_{:?}", background), &image_data);
+
+        let mut dataset: CocoDataset =
+            serde_json::from_str(COCO_JSON).expect("Unable to parse COCO");
+        dataset
+            .annotations
+            .retain(|annotation| annotation.image_id == 1);
+
+        let import = CocoImporter::convert(
+            &dataset,
+            "instances.json",
+            PathBuf::from(&images).as_path(),
+            &FileMetadata {
+                background,
+                ..Default::default()
+            },
+        )
+        .expect("Unable to convert COCO");
+
+        let valid = import
+            .pairs
+            .iter()
+            .filter_map(|pair| match pair {
+                yolo_io::PairingResult::Valid(pair) => Some(pair.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let missing = import
+            .pairs
+            .iter()
+            .filter(|pair| {
+                matches!(
+                    pair,
+                    yolo_io::PairingResult::Invalid(PairingError::LabelFileMissing(_))
+                )
+            })
+            .count();
+
+        assert_eq!(valid.len(), expected_valid);
+        assert_eq!(missing, 3 - expected_valid);
+        assert!(valid
+            .iter()
+            .filter(|pair| pair.name != "first")
+            .all(|pair| pair
+                .label_file
+                .as_ref()
+                .is_some_and(YoloFile::is_background)));
+    }
+
+    #[rstest]
+    fn test_coco_import_reports_crowd_annotations(image_data: ImageBuffer<Rgb<u8>, Vec<u8>>) {
+        let (images, _) = create_coco_dataset("coco_crowd", &image_data);
+
+        let mut dataset: CocoDataset =
+            serde_json::from_str(COCO_JSON).expect("Unable to parse COCO");
+        dataset.annotations[0].iscrowd = 1;
+
+        let import = CocoImporter::convert(
+            &dataset,
+            "instances.json",
+            PathBuf::from(&images).as_path(),
+            &FileMetadata {
+                background: BackgroundPolicy::Reject,
+                ..Default::default()
+            },
+        )
+        .expect("Unable to convert COCO");
+
+        let crowd = import
+            .pairs
+            .iter()
+            .find_map(|pair| match pair {
+                yolo_io::PairingResult::Invalid(PairingError::UnmappableAnnotation(annotation))
+                    if annotation.annotation == Some("10".to_string()) =>
+                {
+                    Some(annotation.clone())
+                }
+                _ => None,
+            })
+            .expect("Expected the crowd annotation to be reported");
+
+        assert_eq!(crowd.reason, "crowd annotation");
+        assert!(!import.pairs.iter().any(|pair| matches!(
+            pair,
+            yolo_io::PairingResult::Valid(pair) if pair.name == "first"
+        )));
+    }
+
+    #[rstest]
+    #[case::reject(RowErrorPolicy::Reject, None)]
+    #[case::report_all(RowErrorPolicy::ReportAll, None)]
+    #[case::drop_rows(RowErrorPolicy::DropRows, Some(2))]
+    fn test_coco_import_applies_row_error_policy(
+        image_data: ImageBuffer<Rgb<u8>, Vec<u8>>,
+        #[case] row_errors: RowErrorPolicy,
+        #[case] expected_entries: Option<usize>,
+    ) {
+        let (images, _) =
+            create_coco_dataset(&format!("coco_row_errors_{:?}", row_errors), &image_data);
+
+        // Annotation 14 duplicates annotation 11 within the tolerance.
+        let mut dataset: CocoDataset =
+            serde_json::from_str(COCO_JSON).expect("Unable to parse COCO");
+        let mut duplicate = dataset.annotations[1].clone();
+        duplicate.id = 14;
+        duplicate.bbox = [0.0, 0.0, 80.0, 161.0];
+        dataset.annotations.push(duplicate);
+
+        let import = CocoImporter::convert(
+            &dataset,
+            "instances.json",
+            PathBuf::from(&images).as_path(),
+            &FileMetadata {
+                duplicate_tolerance: 0.01,
+                row_errors,
+                ..Default::default()
+            },
+        )
+        .expect("Unable to convert COCO");
+
+        let duplicate = import
+            .pairs
+            .iter()
+            .find_map(|pair| match pair {
+                PairingResult::Invalid(PairingError::UnmappableAnnotation(annotation))
+                    if annotation.annotation == Some("14".to_string()) =>
+                {
+                    Some(annotation.clone())
+                }
+                _ => None,
+            })
+            .expect("Expected the duplicate annotation to be reported");
+        let first = import.pairs.iter().find_map(|pair| match pair {
+            PairingResult::Valid(pair) if pair.name == "first" => pair.label_file.clone(),
+            _ => None,
+        });
+
+        assert_eq!(duplicate.reason, "duplicate of annotation 11");
+        assert_eq!(
+            first.map(|label_file| label_file.entries.len()),
+            expected_entries
+        );
+    }
+
+    #[cfg(feature = "image-validation")]
+    #[rstest]
+    fn test_coco_import_validates_images(image_data: ImageBuffer<Rgb<u8>, Vec<u8>>) {
+        let (images, _) = create_coco_dataset("coco_image_validation", &image_data);
+        fs::write(format!("{}/first.jpg", images), b"not an image").expect("Unable to write image");
+
+        let dataset: CocoDataset = serde_json::from_str(COCO_JSON).expect("Unable to parse COCO");
+
+        let import = CocoImporter::convert(
+            &dataset,
+            "instances.json",
+            PathBuf::from(&images).as_path(),
+            &FileMetadata {
+                image_validation: ImageValidation::Header,
+                ..Default::default()
+            },
+        )
+        .expect("Unable to convert COCO");
+
+        assert!(import.pairs.iter().any(|pair| matches!(
+            pair,
+            PairingResult::Invalid(PairingError::UnreadableImage(path)) if path.ends_with("first.jpg")
+        )));
+        assert!(!import.pairs.iter().any(|pair| matches!(
+            pair,
+            PairingResult::Valid(pair) if pair.name == "first"
+        )));
+    }
+
+    #[cfg(not(feature = "image-validation"))]
+    #[test]
+    fn test_coco_import_requires_image_validation_feature() {
+        let dataset: CocoDataset = serde_json::from_str(COCO_JSON).expect("Unable to parse COCO");
+
+        let result = CocoImporter::convert(
+            &dataset,
+            "instances.json",
+            PathBuf::from(TEST_SANDBOX_DIR).as_path(),
+            &FileMetadata {
+                image_validation: ImageValidation::Header,
+                ..Default::default()
+            },
+        );
+
+        assert!(matches!(
+            result,
+            Err(ImportError::ImageValidationUnavailable)
+        ));
+    }
+
+    #[rstest]
+    fn test_coco_project_exports_yolo_labels(
+        image_data: ImageBuffer<Rgb<u8>, Vec<u8>>,
+        create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let (images, annotations) = create_coco_dataset("coco_export_source", &image_data);
+        let export_directory = format!("{}/coco_export", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&export_directory);
+
+        let mut config = create_yolo_project_config;
+        config.source_paths.images = images;
+        config.export.paths.root = PathBuf::from(&export_directory);
+        config.export.split = Split {
+            train: 1.0,
+            validation: 0.0,
+            test: 0.0,
+            ..Default::default()
+        };
+
+        let project = YoloProject::from_coco(&config, &annotations).expect("Unable to import");
+        YoloProjectExporter::export(project).expect("Unable to export project");
+
+        let label = fs::read_to_string(format!("{}/train/labels/first.txt", export_directory))
+            .expect("Unable to read exported label");
+
+        assert_eq!(label, "1 0.5 0.375 0.5 0.25\n0 0.05 0.1 0.1 0.2");
+    }
+
+    #[test]
+    fn test_coco_import_missing_file_returns_error() {
+        let result = CocoImporter::import(
+            format!("{}/missing_instances.json", TEST_SANDBOX_DIR),
+            TEST_SANDBOX_DIR,
+            &FileMetadata::default(),
+        );
+
+        assert!(matches!(result, Err(ImportError::ReadFile(_))));
+    }
+
+    #[rstest]
+    fn test_coco_export_writes_pixel_boxes_per_split(
+        image_data: ImageBuffer<Rgb<u8>, Vec<u8>>,
+        create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let this_test_directory = format!("{}/coco_export_yolo_source", TEST_SANDBOX_DIR);
+        let export_directory = format!("{}/coco_export_yolo", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&this_test_directory);
+        let _ = fs::remove_dir_all(&export_directory);
+
+        let image_file = PathBuf::from(format!("{}/box.jpg", this_test_directory));
+        create_image_file(&image_file, &image_data);
+
+        let label_file = PathBuf::from(format!("{}/box.txt", this_test_directory));
+        create_dir_and_write_file(&label_file, "1 0.5 0.375 0.5 0.25");
+
+        let mut config = create_yolo_project_config;
+        config.source_paths.images = this_test_directory.clone();
+        config.source_paths.labels = this_test_directory.clone();
+        config.export.paths.root = PathBuf::from(&export_directory);
+        config.export.split = Split {
+            train: 1.0,
+            validation: 0.0,
+            test: 0.0,
+            ..Default::default()
+        };
+
+        let project = YoloProject::new(&config).expect("Unable to create project");
+        CocoExporter::export(project).expect("Unable to export COCO");
+
+        let json = fs::read_to_string(format!("{}/instances_train.json", export_directory))
+            .expect("Unable to read COCO file");
+        let dataset: CocoDataset = serde_json::from_str(&json).expect("Invalid COCO file");
+
+        assert_eq!(dataset.images.len(), 1);
+        assert_eq!(dataset.images[0].file_name, "box.jpg");
+        assert_eq!(dataset.images[0].width, 800);
+        assert_eq!(dataset.annotations.len(), 1);
+        assert_eq!(dataset.annotations[0].category_id, 2);
+        assert_eq!(dataset.annotations[0].bbox, [200.0, 200.0, 400.0, 200.0]);
+        assert_eq!(dataset.categories[0].name, "person");
+
+        assert!(PathBuf::from(format!("{}/train/images/box.jpg", export_directory)).exists());
+
+        let validation =
+            fs::read_to_string(format!("{}/instances_validation.json", export_directory))
+                .expect("Unable to read COCO file");
+        let validation: CocoDataset = serde_json::from_str(&validation).unwrap();
+
+        assert!(validation.images.is_empty());
+    }
+
+    #[rstest]
+    fn test_coco_export_writes_polygons_poses_and_oriented_boxes(
+        image_data: ImageBuffer<Rgb<u8>, Vec<u8>>,
+    ) {
+        let image_file = PathBuf::from(format!(
+            "{}/coco_export_shapes/shapes.jpg",
+            TEST_SANDBOX_DIR
+        ));
+        create_image_file(&image_file, &image_data);
+
+        let pair = ImageLabelPair {
+            name: "shapes".to_string(),
+            image_path: Some(image_file),
+            label_file: Some(YoloFile {
+                stem: "shapes".to_string(),
+                path: "shapes.txt".to_string(),
+                entries: vec![],
+                polygons: vec![YoloPolygon {
+                    class: 0,
+                    points: vec![(0.25, 0.25), (0.75, 0.25), (0.25, 0.75)],
+                }],
+                poses: vec![YoloPose {
+                    entry: YoloEntry {
+                        class: 0,
+                        x_center: 0.5,
+                        y_center: 0.5,
+                        width: 0.5,
+                        height: 0.5,
+                    },
+                    keypoints: vec![
+                        YoloKeypoint {
+                            x: 0.5,
+                            y: 0.25,
+                            visibility: Some(2),
+                        },
+                        YoloKeypoint {
+                            x: 0.0,
+                            y: 0.0,
+                            visibility: Some(0),
+                        },
+                    ],
+                }],
+                oriented_boxes: vec![YoloOrientedBox {
+                    class: 1,
+                    corners: [(0.5, 0.25), (0.75, 0.5), (0.5, 0.75), (0.25, 0.5)],
+                }],
+                source: LabelSource::Yolo,
+                warnings: vec![],
+                formatting: vec![],
+            }),
+        };
+
+        let class_map = [(0, "person".to_string()), (1, "car".to_string())]
+            .into_iter()
+            .collect();
+
+        let dataset = CocoExporter::to_dataset(&[pair], &class_map).expect("Unable to convert");
+
+        assert_eq!(dataset.annotations.len(), 3);
+
+        let polygon = &dataset.annotations[0];
+        assert_eq!(polygon.bbox, [200.0, 200.0, 400.0, 400.0]);
+        assert_eq!(polygon.area, 80000.0);
+        assert_eq!(
+            polygon.segmentation,
+            Some(CocoSegmentation::Polygons(vec![vec![
+                200.0, 200.0, 600.0, 200.0, 200.0, 600.0
+            ]]))
+        );
+
+        let pose = &dataset.annotations[1];
+        assert_eq!(pose.bbox, [200.0, 200.0, 400.0, 400.0]);
+        assert_eq!(pose.keypoints, Some(vec![400.0, 200.0, 2.0, 0.0, 0.0, 0.0]));
+        assert_eq!(pose.num_keypoints, Some(1));
+        assert_eq!(pose.segmentation, None);
+
+        let oriented_box = &dataset.annotations[2];
+        assert_eq!(oriented_box.category_id, 2);
+        assert_eq!(oriented_box.bbox, [200.0, 200.0, 400.0, 400.0]);
+        assert_eq!(oriented_box.area, 80000.0);
+    }
+
+    #[rstest]
+    fn test_coco_copy_export_replaces_links_from_earlier_export(
+        image_data: ImageBuffer<Rgb<u8>, Vec<u8>>,
+        create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let this_test_directory = format!("{}/coco_relink_source", TEST_SANDBOX_DIR);
+        let export_directory = format!("{}/coco_relink", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&this_test_directory);
+        let _ = fs::remove_dir_all(&export_directory);
+
+        let image_file = PathBuf::from(format!("{}/box.jpg", this_test_directory));
+        create_image_file(&image_file, &image_data);
+        create_dir_and_write_file(
+            &PathBuf::from(format!("{}/box.txt", this_test_directory)),
+            "0 0.5 0.5 0.5 0.5",
+        );
+        let image_bytes = fs::read(&image_file).expect("Unable to read image");
+
+        let mut config = create_yolo_project_config;
+        config.source_paths.images = this_test_directory.clone();
+        config.source_paths.labels = this_test_directory.clone();
+        config.export.paths.root = PathBuf::from(&export_directory);
+        config.export.overwrite = OverwritePolicy::Merge;
+        config.export.link_mode = LinkMode::AbsoluteSymlink;
+        config.export.split = Split {
+            train: 1.0,
+            validation: 0.0,
+            test: 0.0,
+            ..Default::default()
+        };
+
+        let project = YoloProject::new(&config).expect("Unable to create project");
+        YoloProjectExporter::export(project).expect("Unable to export project");
+
+        let exported_image = PathBuf::from(format!("{}/train/images/box.jpg", export_directory));
+        assert!(fs::symlink_metadata(&exported_image)
+            .expect("Unable to read exported image")
+            .file_type()
+            .is_symlink());
+
+        config.export.link_mode = LinkMode::Copy;
+        let project = YoloProject::new(&config).expect("Unable to create project");
+        CocoExporter::export(project).expect("Unable to export project");
+
+        assert!(!fs::symlink_metadata(&exported_image)
+            .expect("Unable to read exported image")
+            .file_type()
+            .is_symlink());
+        assert_eq!(
+            fs::read(&image_file).expect("Unable to read image"),
+            image_bytes
+        );
+        assert_eq!(
+            fs::read(&exported_image).expect("Unable to read exported image"),
+            image_bytes
+        );
+    }
+}
@@ -6,7 +6,10 @@ use std::{
 use hashbrown::HashMap;
 use image::{ImageBuffer, Rgb};
 use rstest::fixture;
-use yolo_io::{Export, Paths, SourcePaths, Split, YoloProjectConfig};
+use yolo_io::{
+    BackgroundPolicy, Export, ExportLayout, ImageValidation, LinkMode, OverwritePolicy, Paths,
+    RowErrorPolicy, SourcePaths, Split, YoloProjectConfig,
+};
 
 #[allow(dead_code)]
 pub const TEST_SANDBOX_DIR: &str = "tests/sandbox";
@@ -92,6 +95,7 @@ pub fn create_yolo_project_config() -> YoloProjectConfig {
         source_paths: SourcePaths {
             images: String::from("tests/sandbox/"),
             labels: String::from("tests/sandbox/"),
+            cache: None,
         },
         r#type: String::from("yolo"),
         project_name: String::from("test_project"),
@@ -108,7 +112,17 @@ pub fn create_yolo_project_config() -> YoloProjectConfig {
                 train: 0.80,
                 validation: 0.20,
                 test: 0.0,
+                ..Default::default()
             },
+            keypoints: None,
+            oriented_bounding_boxes: false,
+            link_mode: LinkMode::Copy,
+            layout: ExportLayout::Directories,
+            row_errors: RowErrorPolicy::Reject,
+            strict_formatting: false,
+            background: BackgroundPolicy::Reject,
+            image_validation: ImageValidation::Off,
+            overwrite: OverwritePolicy::FailIfNotEmpty,
         },
     }
 }
@@ -11,7 +11,12 @@ mod tests {
     };
     use image::{ImageBuffer, Rgb};
     use rstest::rstest;
-    use yolo_io::{Split, YoloProject, YoloProjectConfig, YoloProjectExporter};
+    use sha2::{Digest, Sha256};
+    use yolo_io::{
+        BackgroundPolicy, ClassDistribution, ExportError, ExportLayout, ExportPlan, FileOperation,
+        LinkMode, Manifest, OverwritePolicy, RowErrorPolicy, Split, SplitGroup, SplitMode,
+        SplitReport, YoloDataQualityReport, YoloProject, YoloProjectConfig, YoloProjectExporter,
+    };
 
     fn run_export(
         mut create_yolo_project_config: YoloProjectConfig,
@@ -67,6 +72,7 @@ mod tests {
                 train: 0.6,
                 validation: 0.2,
                 test: 0.2,
+                ..Default::default()
             },
         );
         let train_image_path = exported_config.export.paths.root.join("train/images");
@@ -105,6 +111,7 @@ mod tests {
                 train: 0.3,
                 validation: 0.3,
                 test: 0.4,
+                ..Default::default()
             },
         );
 
@@ -147,6 +154,7 @@ mod tests {
                 train: 0.6,
                 validation: 0.2,
                 test: 0.2,
+                ..Default::default()
             },
         );
 
@@ -181,6 +189,7 @@ names:
                 train: 0.6,
                 validation: 0.2,
                 test: 0.2,
+                ..Default::default()
             },
         );
 
@@ -210,4 +219,1214 @@ names:
             }
         }
     }
+
+    fn split_members(config: &YoloProjectConfig, split: &str) -> Vec<String> {
+        let mut members = fs::read_dir(config.export.paths.root.join(split).join("images"))
+            .unwrap()
+            .map(|entry| {
+                entry
+                    .unwrap()
+                    .path()
+                    .file_stem()
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect::<Vec<String>>();
+
+        members.sort();
+        members
+    }
+
+    #[rstest]
+    fn test_seeded_split_is_reproducible_and_stable(create_yolo_project_config: YoloProjectConfig) {
+        let split = Split {
+            train: 0.6,
+            validation: 0.2,
+            test: 0.2,
+            seed: Some(42),
+            ..Default::default()
+        };
+        let image_data = image_data();
+
+        let first = run_export(
+            create_yolo_project_config.clone(),
+            "test_seeded_split_first".to_string(),
+            image_data.clone(),
+            "jpg",
+            "txt",
+            20,
+            split.clone(),
+        );
+
+        let second = run_export(
+            create_yolo_project_config.clone(),
+            "test_seeded_split_second".to_string(),
+            image_data.clone(),
+            "jpg",
+            "txt",
+            20,
+            split.clone(),
+        );
+
+        let grown = run_export(
+            create_yolo_project_config,
+            "test_seeded_split_grown".to_string(),
+            image_data,
+            "jpg",
+            "txt",
+            30,
+            split,
+        );
+
+        for name in ["train", "validation", "test"] {
+            assert_eq!(split_members(&first, name), split_members(&second, name));
+
+            let original = split_members(&first, name);
+            let grown_members = split_members(&grown, name);
+            assert!(original.iter().all(|stem| grown_members.contains(stem)));
+        }
+
+        assert_eq!(split_members(&first, "test"), vec!["test_7"]);
+        assert_eq!(
+            split_members(&first, "validation"),
+            vec!["test_10", "test_12", "test_13", "test_17", "test_8"]
+        );
+    }
+
+    #[rstest]
+    fn test_stratified_split_balances_rare_classes(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let export_source_dir = format!("{}/export_source_stratified", TEST_SANDBOX_DIR);
+        let export_out_dir = format!("{}/export_stratified", TEST_SANDBOX_DIR);
+
+        let _ = fs::remove_dir_all(&export_source_dir);
+        let _ = fs::remove_dir_all(&export_out_dir);
+
+        create_dir(&export_source_dir);
+
+        let image_data = image_data();
+        for i in 0..20 {
+            let image_path = format!("{}/test_{}.jpg", export_source_dir, i);
+            let label_path = format!("{}/test_{}.txt", export_source_dir, i);
+            image_data.save(&image_path).expect("Unable to save image");
+
+            let label = if i % 4 == 0 {
+                "0 0.25 0.25 0.2 0.2\n1 0.75 0.75 0.2 0.2"
+            } else {
+                "0 0.25 0.25 0.2 0.2"
+            };
+            create_dir_and_write_file(std::path::Path::new(&label_path), label);
+        }
+
+        create_yolo_project_config.source_paths.images = export_source_dir.clone();
+        create_yolo_project_config.source_paths.labels = export_source_dir.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.split = Split {
+            train: 0.6,
+            validation: 0.2,
+            test: 0.2,
+            seed: Some(7),
+            mode: SplitMode::Stratified,
+            ..Default::default()
+        };
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        YoloProjectExporter::export(project).expect("Unable to export project");
+
+        let report: SplitReport = serde_json::from_str(
+            &fs::read_to_string(format!("{}/split_report.json", export_out_dir))
+                .expect("Unable to read split report"),
+        )
+        .expect("Unable to parse split report");
+
+        assert_eq!(report.train_images, 12);
+        assert_eq!(report.validation_images, 4);
+        assert_eq!(report.test_images, 4);
+        assert_eq!(
+            report.classes,
+            vec![
+                ClassDistribution {
+                    class: 0,
+                    name: "person".to_string(),
+                    train: 12,
+                    validation: 4,
+                    test: 4,
+                },
+                ClassDistribution {
+                    class: 1,
+                    name: "car".to_string(),
+                    train: 3,
+                    validation: 1,
+                    test: 1,
+                },
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case::stem_pattern(
+        "group_stem",
+        SplitGroup::StemPattern(r"^clip_(\d+)_".to_string())
+    )]
+    #[case::parent_directory("group_parent", SplitGroup::ParentDirectory)]
+    fn test_grouped_split_keeps_groups_together(
+        mut create_yolo_project_config: YoloProjectConfig,
+        #[case] export_name: &str,
+        #[case] group: SplitGroup,
+    ) {
+        let export_source_dir = format!("{}/export_source_{}", TEST_SANDBOX_DIR, export_name);
+        let export_out_dir = format!("{}/export_{}", TEST_SANDBOX_DIR, export_name);
+
+        let _ = fs::remove_dir_all(&export_source_dir);
+        let _ = fs::remove_dir_all(&export_out_dir);
+
+        let image_data = image_data();
+        for clip in 0..6 {
+            for frame in 0..3 {
+                let stem = format!(
+                    "{}/clip_{}/clip_{}_frame_{}",
+                    export_source_dir, clip, clip, frame
+                );
+                create_dir_and_write_file(
+                    std::path::Path::new(&format!("{}.txt", stem)),
+                    "0 0.5 0.5 0.5 0.5",
+                );
+                image_data
+                    .save(format!("{}.jpg", stem))
+                    .expect("Unable to save image");
+            }
+        }
+
+        create_yolo_project_config.source_paths.images = export_source_dir.clone();
+        create_yolo_project_config.source_paths.labels = export_source_dir.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.split = Split {
+            train: 0.5,
+            validation: 0.3,
+            test: 0.2,
+            group: Some(group),
+            ..Default::default()
+        };
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        YoloProjectExporter::export(project).expect("Unable to export project");
+
+        let splits = ["train", "validation", "test"]
+            .map(|split| split_members(&create_yolo_project_config, split));
+
+        assert_eq!(
+            splits.iter().map(|members| members.len()).sum::<usize>(),
+            18
+        );
+
+        for clip in 0..6 {
+            let prefix = format!("clip_{}_", clip);
+            let holding_clip = splits
+                .iter()
+                .filter(|members| members.iter().any(|stem| stem.starts_with(&prefix)))
+                .count();
+
+            assert_eq!(holding_clip, 1, "clip {} was split across sets", clip);
+        }
+    }
+
+    #[rstest]
+    fn test_grouped_split_rejects_invalid_pattern(create_yolo_project_config: YoloProjectConfig) {
+        let exported_config = run_export(
+            create_yolo_project_config,
+            "test_bad_group".to_string(),
+            image_data(),
+            "jpg",
+            "txt",
+            1,
+            Split::default(),
+        );
+
+        let mut config = exported_config;
+        config.export.split.group = Some(SplitGroup::StemPattern("clip_(".to_string()));
+
+        let project = YoloProject::new(&config).expect("Unable to create project");
+
+        let result = YoloProjectExporter::export(project);
+
+        assert!(matches!(result, Err(ExportError::InvalidGroupPattern(_))));
+    }
+
+    fn create_pairs(source_dir: &str, num_pairs: usize) {
+        let _ = fs::remove_dir_all(source_dir);
+        create_dir(source_dir);
+
+        let image_data = image_data();
+        for i in 0..num_pairs {
+            image_data
+                .save(format!("{}/test_{}.jpg", source_dir, i))
+                .expect("Unable to save image");
+            create_dir_and_write_file(
+                std::path::Path::new(&format!("{}/test_{}.txt", source_dir, i)),
+                "0 0.5 0.5 0.5 0.5",
+            );
+        }
+    }
+
+    #[rstest]
+    fn test_k_fold_export_rotates_validation_partition(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let export_source_dir = format!("{}/export_source_k_fold", TEST_SANDBOX_DIR);
+        let export_out_dir = format!("{}/export_k_fold", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&export_out_dir);
+
+        create_pairs(&export_source_dir, 10);
+
+        create_yolo_project_config.source_paths.images = export_source_dir.clone();
+        create_yolo_project_config.source_paths.labels = export_source_dir.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.split = Split {
+            train: 0.8,
+            validation: 0.0,
+            test: 0.2,
+            seed: Some(3),
+            ..Default::default()
+        };
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        YoloProjectExporter::export_k_fold(project, 4).expect("Unable to export folds");
+
+        let mut all_validation = Vec::<String>::new();
+        let mut test_sets = Vec::<Vec<String>>::new();
+
+        for fold in 0..4 {
+            let mut fold_config = create_yolo_project_config.clone();
+            fold_config.export.paths = fold_config.export.paths.for_fold(fold);
+
+            let train = split_members(&fold_config, "train");
+            let validation = split_members(&fold_config, "validation");
+            let test = split_members(&fold_config, "test");
+
+            assert_eq!(train.len() + validation.len() + test.len(), 10);
+            assert!((train.len() + validation.len()) / 4 <= validation.len());
+            assert!(validation.len() <= (train.len() + validation.len()).div_ceil(4));
+            assert!(validation.iter().all(|stem| !train.contains(stem)));
+            assert!(fold_config
+                .export
+                .paths
+                .get_root()
+                .join("test_project.yaml")
+                .exists());
+
+            all_validation.extend(validation);
+            test_sets.push(test);
+        }
+
+        let validation_count = all_validation.len();
+        all_validation.sort();
+        all_validation.dedup();
+        assert_eq!(all_validation.len(), validation_count);
+        assert_eq!(all_validation.len() + test_sets[0].len(), 10);
+        assert!(test_sets.iter().all(|test| *test == test_sets[0]));
+    }
+
+    #[rstest]
+    fn test_k_fold_export_resumes_interrupted_run(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let export_source_dir = format!("{}/export_source_k_fold_resume", TEST_SANDBOX_DIR);
+        let export_out_dir = format!("{}/export_k_fold_resume", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&export_out_dir);
+
+        create_pairs(&export_source_dir, 6);
+
+        create_yolo_project_config.source_paths.images = export_source_dir.clone();
+        create_yolo_project_config.source_paths.labels = export_source_dir.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.overwrite = OverwritePolicy::FailIfNotEmpty;
+        create_yolo_project_config.export.split = Split {
+            train: 1.0,
+            validation: 0.0,
+            test: 0.0,
+            seed: Some(3),
+            ..Default::default()
+        };
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        YoloProjectExporter::export_k_fold(project.clone(), 3).expect("Unable to export folds");
+
+        // Leave behind what a run interrupted while staging the last fold
+        // would have.
+        let last_fold = PathBuf::from(format!("{}/fold_2", export_out_dir));
+        let staging = PathBuf::from(format!("{}/.fold_2.staging", export_out_dir));
+        fs::rename(&last_fold, &staging).expect("Unable to move fold");
+        fs::remove_file(staging.join("manifest.json")).expect("Unable to remove manifest");
+
+        let kept_manifest = PathBuf::from(format!("{}/fold_0/manifest.json", export_out_dir));
+        let kept_at = fs::metadata(&kept_manifest)
+            .and_then(|metadata| metadata.modified())
+            .expect("Unable to read modification time");
+
+        YoloProjectExporter::export_k_fold(project.clone(), 3).expect("Unable to resume folds");
+
+        assert!(last_fold.join("manifest.json").exists());
+        assert!(!staging.exists());
+        assert_eq!(
+            fs::metadata(&kept_manifest)
+                .and_then(|metadata| metadata.modified())
+                .expect("Unable to read modification time"),
+            kept_at
+        );
+
+        // Anything else in the root is still refused.
+        create_dir_and_write_file(
+            std::path::Path::new(&format!("{}/notes.txt", export_out_dir)),
+            "keep",
+        );
+
+        assert!(matches!(
+            YoloProjectExporter::export_k_fold(project, 3),
+            Err(ExportError::ExportRootNotEmpty(_))
+        ));
+    }
+
+    #[rstest]
+    fn test_k_fold_export_rejects_too_many_folds(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let export_source_dir = format!("{}/export_source_k_fold_count", TEST_SANDBOX_DIR);
+        create_pairs(&export_source_dir, 3);
+
+        create_yolo_project_config.source_paths.images = export_source_dir.clone();
+        create_yolo_project_config.source_paths.labels = export_source_dir;
+        create_yolo_project_config.export.paths.root =
+            PathBuf::from(format!("{}/export_k_fold_count", TEST_SANDBOX_DIR));
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        let result = YoloProjectExporter::export_k_fold(project, 5);
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Unable to create 5 folds from 3 groups of pairs"
+        );
+    }
+
+    #[rstest]
+    #[case::hardlink("link_hard", LinkMode::Hardlink)]
+    #[case::relative_symlink("link_relative", LinkMode::RelativeSymlink)]
+    #[case::absolute_symlink("link_absolute", LinkMode::AbsoluteSymlink)]
+    fn test_export_links_files(
+        mut create_yolo_project_config: YoloProjectConfig,
+        #[case] export_name: &str,
+        #[case] link_mode: LinkMode,
+    ) {
+        let export_source_dir = format!("{}/export_source_{}", TEST_SANDBOX_DIR, export_name);
+        let export_out_dir = format!("{}/export_{}", TEST_SANDBOX_DIR, export_name);
+        let _ = fs::remove_dir_all(&export_out_dir);
+
+        create_pairs(&export_source_dir, 2);
+
+        create_yolo_project_config.source_paths.images = export_source_dir.clone();
+        create_yolo_project_config.source_paths.labels = export_source_dir.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.split = Split {
+            train: 1.0,
+            validation: 0.0,
+            test: 0.0,
+            ..Default::default()
+        };
+        create_yolo_project_config.export.link_mode = link_mode;
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        YoloProjectExporter::export(project).expect("Unable to export project");
+
+        for exported in [
+            format!("{}/train/images/test_0.jpg", export_out_dir),
+            format!("{}/train/labels/test_0.txt", export_out_dir),
+        ] {
+            let exported = PathBuf::from(exported);
+            let is_symlink = fs::symlink_metadata(&exported)
+                .expect("Exported file should exist")
+                .file_type()
+                .is_symlink();
+
+            match link_mode {
+                LinkMode::Hardlink => assert!(!is_symlink),
+                LinkMode::RelativeSymlink => {
+                    assert!(is_symlink);
+                    assert!(fs::read_link(&exported).unwrap().is_relative());
+                }
+                LinkMode::AbsoluteSymlink => {
+                    assert!(is_symlink);
+                    assert!(fs::read_link(&exported).unwrap().is_absolute());
+                }
+                LinkMode::Copy => unreachable!(),
+            }
+        }
+
+        assert_eq!(
+            fs::read_to_string(format!("{}/train/labels/test_1.txt", export_out_dir))
+                .expect("Linked label should be readable"),
+            "0 0.5 0.5 0.5 0.5"
+        );
+    }
+
+    #[rstest]
+    fn test_export_reports_link_failure(mut create_yolo_project_config: YoloProjectConfig) {
+        let export_source_dir = format!("{}/export_source_link_failure", TEST_SANDBOX_DIR);
+        let export_out_dir = format!("{}/export_link_failure", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&export_out_dir);
+
+        create_pairs(&export_source_dir, 1);
+
+        create_yolo_project_config.source_paths.images = export_source_dir.clone();
+        create_yolo_project_config.source_paths.labels = export_source_dir.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.split = Split {
+            train: 1.0,
+            validation: 0.0,
+            test: 0.0,
+            ..Default::default()
+        };
+        create_yolo_project_config.export.link_mode = LinkMode::AbsoluteSymlink;
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        let staging = YoloProjectExporter::plan(&project)
+            .expect("Unable to plan export")
+            .staging_path();
+        let _ = fs::remove_dir_all(&staging);
+        create_dir(&format!("{}/train/images/test_0.jpg", staging.display()));
+
+        let result = YoloProjectExporter::export(project);
+
+        assert!(matches!(
+            result,
+            Err(ExportError::FailedToLinkFile(_, _, _))
+        ));
+        assert!(!PathBuf::from(&export_out_dir).exists());
+        assert!(staging.exists());
+    }
+
+    #[rstest]
+    fn test_export_is_staged_and_moved_into_place(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let export_source_dir = format!("{}/export_source_staged", TEST_SANDBOX_DIR);
+        let export_out_dir = format!("{}/export_staged", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&export_out_dir);
+
+        create_pairs(&export_source_dir, 2);
+
+        create_yolo_project_config.source_paths.images = export_source_dir.clone();
+        create_yolo_project_config.source_paths.labels = export_source_dir.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.split = Split {
+            train: 1.0,
+            validation: 0.0,
+            test: 0.0,
+            ..Default::default()
+        };
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        let plan = YoloProjectExporter::plan(&project).expect("Unable to plan export");
+        let staging = plan.staging_path();
+
+        assert_eq!(
+            staging,
+            std::env::current_dir()
+                .unwrap()
+                .join(format!("{}/.export_staged.staging", TEST_SANDBOX_DIR))
+        );
+
+        plan.execute().expect("Unable to execute plan");
+
+        assert!(!staging.exists());
+        for file in &plan.files {
+            assert!(file.destination.exists());
+        }
+
+        // Exporting again into the populated root merges into it.
+        let plan = ExportPlan {
+            overwrite: OverwritePolicy::Merge,
+            ..plan
+        };
+        plan.execute().expect("Unable to execute plan again");
+
+        assert!(!staging.exists());
+        for file in &plan.files {
+            assert!(file.destination.exists());
+        }
+    }
+
+    #[rstest]
+    fn test_export_resumes_from_staging(mut create_yolo_project_config: YoloProjectConfig) {
+        let export_source_dir = format!("{}/export_source_resume", TEST_SANDBOX_DIR);
+        let export_out_dir = format!("{}/export_resume", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&export_out_dir);
+
+        create_pairs(&export_source_dir, 2);
+
+        create_yolo_project_config.source_paths.images = export_source_dir.clone();
+        create_yolo_project_config.source_paths.labels = export_source_dir.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.split = Split {
+            train: 1.0,
+            validation: 0.0,
+            test: 0.0,
+            ..Default::default()
+        };
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        let plan = YoloProjectExporter::plan(&project).expect("Unable to plan export");
+        let staging = plan.staging_path();
+        let _ = fs::remove_dir_all(&staging);
+
+        // Leave behind what an interrupted run would have: one image
+        // fully copied, one truncated and one from a different plan. The
+        // complete image is marked read-only, which copying over it would
+        // undo.
+        let staged_images = staging.join("train/images");
+        create_dir(&staged_images.to_string_lossy());
+
+        let complete = staged_images.join("test_0.jpg");
+        fs::copy(format!("{}/test_0.jpg", export_source_dir), &complete)
+            .expect("Unable to stage image");
+        let mut permissions = fs::metadata(&complete)
+            .expect("Unable to read permissions")
+            .permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&complete, permissions).expect("Unable to set permissions");
+
+        fs::write(staged_images.join("test_1.jpg"), b"trunc").expect("Unable to stage image");
+        fs::write(staged_images.join("stale.jpg"), b"stale").expect("Unable to stage image");
+
+        plan.execute().expect("Unable to resume export");
+
+        let exported_images = PathBuf::from(format!("{}/train/images", export_out_dir));
+
+        assert!(fs::metadata(exported_images.join("test_0.jpg"))
+            .expect("Unable to read permissions")
+            .permissions()
+            .readonly());
+        assert!(!exported_images.join("stale.jpg").exists());
+        assert_eq!(
+            fs::read(exported_images.join("test_1.jpg")).expect("Unable to read image"),
+            fs::read(format!("{}/test_1.jpg", export_source_dir)).expect("Unable to read image")
+        );
+        assert!(!staging.exists());
+    }
+
+    #[cfg(unix)]
+    #[rstest]
+    fn test_export_does_not_follow_links_left_in_staging(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let export_source_dir = format!("{}/export_source_staged_links", TEST_SANDBOX_DIR);
+        let export_out_dir = format!("{}/export_staged_links", TEST_SANDBOX_DIR);
+        let outside_dir = format!("{}/export_staged_links_outside", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&export_out_dir);
+        let _ = fs::remove_dir_all(&outside_dir);
+
+        create_pairs(&export_source_dir, 1);
+        create_dir_and_write_file(
+            std::path::Path::new(&format!("{}/keep.txt", outside_dir)),
+            "keep",
+        );
+
+        create_yolo_project_config.source_paths.images = export_source_dir.clone();
+        create_yolo_project_config.source_paths.labels = export_source_dir.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.link_mode = LinkMode::Copy;
+        create_yolo_project_config.export.split = Split {
+            train: 1.0,
+            validation: 0.0,
+            test: 0.0,
+            ..Default::default()
+        };
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        let plan = YoloProjectExporter::plan(&project).expect("Unable to plan export");
+        let staging = plan.staging_path();
+        let _ = fs::remove_dir_all(&staging);
+
+        // Leave behind what an interrupted symbolic link export would
+        // have, plus a link to a directory outside the export.
+        let staged_images = staging.join("train/images");
+        create_dir(&staged_images.to_string_lossy());
+        let source_image = std::env::current_dir()
+            .unwrap()
+            .join(format!("{}/test_0.jpg", export_source_dir));
+        std::os::unix::fs::symlink(&source_image, staged_images.join("test_0.jpg"))
+            .expect("Unable to link image");
+        std::os::unix::fs::symlink(
+            std::env::current_dir().unwrap().join(&outside_dir),
+            staging.join("outside"),
+        )
+        .expect("Unable to link directory");
+
+        plan.execute().expect("Unable to resume export");
+
+        let exported_image = PathBuf::from(format!("{}/train/images/test_0.jpg", export_out_dir));
+        assert!(!fs::symlink_metadata(&exported_image)
+            .expect("Unable to read exported image")
+            .file_type()
+            .is_symlink());
+        assert_eq!(
+            fs::read(&exported_image).expect("Unable to read image"),
+            fs::read(&source_image).expect("Unable to read image")
+        );
+        assert!(PathBuf::from(format!("{}/keep.txt", outside_dir)).exists());
+        assert!(!PathBuf::from(format!("{}/outside", export_out_dir)).exists());
+    }
+
+    #[rstest]
+    fn test_file_list_export_lists_original_images(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let export_source_dir = format!("{}/export_source_file_lists", TEST_SANDBOX_DIR);
+        let export_out_dir = format!("{}/export_file_lists", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&export_source_dir);
+        let _ = fs::remove_dir_all(&export_out_dir);
+
+        create_dir(&format!("{}/images", export_source_dir));
+
+        let image_data = image_data();
+        for i in 0..4 {
+            image_data
+                .save(format!("{}/images/test_{}.jpg", export_source_dir, i))
+                .expect("Unable to save image");
+            create_dir_and_write_file(
+                std::path::Path::new(&format!("{}/labels/test_{}.txt", export_source_dir, i)),
+                "0 0.5 0.5 0.5 0.5",
+            );
+        }
+
+        create_yolo_project_config.source_paths.images = format!("{}/images", export_source_dir);
+        create_yolo_project_config.source_paths.labels = format!("{}/labels", export_source_dir);
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.layout = ExportLayout::FileLists;
+        create_yolo_project_config.export.split = Split {
+            train: 0.5,
+            validation: 0.25,
+            test: 0.25,
+            ..Default::default()
+        };
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        YoloProjectExporter::export(project).expect("Unable to export project");
+
+        let mut listed = Vec::<String>::new();
+        for (list, expected_len) in [("train.txt", 2), ("val.txt", 1), ("test.txt", 1)] {
+            let contents = fs::read_to_string(format!("{}/{}", export_out_dir, list))
+                .expect("Unable to read image list");
+            let lines = contents.lines().map(String::from).collect::<Vec<String>>();
+
+            assert_eq!(lines.len(), expected_len);
+            listed.extend(lines);
+        }
+
+        listed.sort();
+        let expected = (0..4)
+            .map(|i| {
+                std::env::current_dir()
+                    .unwrap()
+                    .join(format!("{}/images/test_{}.jpg", export_source_dir, i))
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect::<Vec<String>>();
+        assert_eq!(listed, expected);
+
+        assert!(!PathBuf::from(format!("{}/train", export_out_dir)).exists());
+
+        let yolo_yaml = fs::read_to_string(format!("{}/test_project.yaml", export_out_dir))
+            .expect("Unable to read yaml");
+
+        let expected_yaml = r#"# Generate by yolo_io - https://github.com/Ladvien/yolo_io
+path: tests/sandbox/export_file_lists
+train: train.txt
+val: val.txt
+test: test.txt
+
+names:
+  0: person
+  1: car
+"#;
+
+        assert_eq!(yolo_yaml, expected_yaml);
+    }
+
+    #[rstest]
+    fn test_file_list_export_rejects_labels_outside_labels_directory(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let export_source_dir = format!("{}/export_source_file_list_location", TEST_SANDBOX_DIR);
+        let export_out_dir = format!("{}/export_file_list_location", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&export_source_dir);
+        let _ = fs::remove_dir_all(&export_out_dir);
+
+        create_dir(&format!("{}/images", export_source_dir));
+        image_data()
+            .save(format!("{}/images/test_0.jpg", export_source_dir))
+            .expect("Unable to save image");
+        let label_path = format!("{}/annotations/test_0.txt", export_source_dir);
+        create_dir_and_write_file(std::path::Path::new(&label_path), "0 0.5 0.5 0.5 0.5");
+
+        create_yolo_project_config.source_paths.images = format!("{}/images", export_source_dir);
+        create_yolo_project_config.source_paths.labels =
+            format!("{}/annotations", export_source_dir);
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.layout = ExportLayout::FileLists;
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        let error = YoloProjectExporter::export(project).expect_err("Expected an error");
+        let expected_path = std::env::current_dir()
+            .unwrap()
+            .join(format!("{}/labels/test_0.txt", export_source_dir));
+
+        assert!(matches!(
+            error,
+            ExportError::FileListLabelLocation(ref path, ref expected)
+                if *path == label_path && *expected == expected_path.to_string_lossy()
+        ));
+        assert!(!PathBuf::from(&export_out_dir).exists());
+    }
+
+    #[rstest]
+    fn test_plan_describes_export_without_writing(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let export_source_dir = format!("{}/export_source_plan", TEST_SANDBOX_DIR);
+        let export_out_dir = format!("{}/export_plan", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&export_out_dir);
+
+        create_pairs(&export_source_dir, 4);
+
+        create_yolo_project_config.source_paths.images = export_source_dir.clone();
+        create_yolo_project_config.source_paths.labels = export_source_dir.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.split = Split {
+            train: 0.5,
+            validation: 0.25,
+            test: 0.25,
+            ..Default::default()
+        };
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        let plan = YoloProjectExporter::plan(&project).expect("Unable to plan export");
+
+        assert!(!PathBuf::from(&export_out_dir).exists());
+
+        assert_eq!(plan.directories.len(), 7);
+        assert_eq!(plan.files.len(), 8);
+        assert_eq!(
+            plan.files
+                .iter()
+                .filter(|file| file.split == "train")
+                .count(),
+            4
+        );
+        assert!(plan
+            .files
+            .iter()
+            .all(|file| file.operation == FileOperation::Link(LinkMode::Copy)));
+        assert!(plan.yaml.contains("train: train/"));
+
+        let json = serde_json::to_string(&plan).expect("Unable to serialize plan");
+        let reviewed: ExportPlan = serde_json::from_str(&json).expect("Unable to parse plan");
+        assert_eq!(reviewed, plan);
+
+        reviewed.execute().expect("Unable to execute plan");
+
+        for file in &plan.files {
+            assert!(file.destination.exists());
+        }
+        assert_eq!(
+            fs::read_to_string(&plan.yaml_path).expect("Unable to read yaml"),
+            plan.yaml
+        );
+    }
+
+    #[rstest]
+    fn test_export_writes_manifest(mut create_yolo_project_config: YoloProjectConfig) {
+        let export_source_dir = format!("{}/export_source_manifest", TEST_SANDBOX_DIR);
+        let export_out_dir = format!("{}/export_manifest", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&export_out_dir);
+
+        create_pairs(&export_source_dir, 3);
+        create_dir_and_write_file(
+            std::path::Path::new(&format!("{}/test_0.txt", export_source_dir)),
+            "0 0.25 0.25 0.2 0.2\n1 0.75 0.75 0.2 0.2",
+        );
+
+        create_yolo_project_config.source_paths.images = export_source_dir.clone();
+        create_yolo_project_config.source_paths.labels = export_source_dir.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.split = Split {
+            train: 1.0,
+            validation: 0.0,
+            test: 0.0,
+            ..Default::default()
+        };
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        YoloProjectExporter::export(project).expect("Unable to export project");
+
+        let manifest: Manifest = serde_json::from_str(
+            &fs::read_to_string(format!("{}/manifest.json", export_out_dir))
+                .expect("Unable to read manifest"),
+        )
+        .expect("Unable to parse manifest");
+
+        assert_eq!(manifest.files.len(), 8);
+
+        let (described, files) = manifest.files.split_at(2);
+        assert_eq!(
+            described
+                .iter()
+                .map(|entry| entry.destination.clone())
+                .collect::<Vec<PathBuf>>(),
+            vec![
+                PathBuf::from(format!("{}/test_project.yaml", export_out_dir)),
+                PathBuf::from(format!("{}/split_report.json", export_out_dir)),
+            ]
+        );
+
+        for entry in described {
+            let contents = fs::read(&entry.destination).expect("Unable to read exported file");
+
+            assert_eq!(entry.split, "");
+            assert_eq!(entry.source, None);
+            assert_eq!(entry.size, contents.len() as u64);
+            assert_eq!(entry.sha256, format!("{:x}", Sha256::digest(&contents)));
+        }
+
+        for entry in files {
+            let contents = fs::read(&entry.destination).expect("Unable to read exported file");
+            let source = entry.source.as_ref().expect("Entry should have a source");
+
+            assert_eq!(entry.split, "train");
+            assert_eq!(contents, fs::read(source).expect("Unable to read source"));
+            assert_eq!(entry.size, contents.len() as u64);
+            assert_eq!(entry.sha256, format!("{:x}", Sha256::digest(&contents)));
+            assert_eq!(
+                entry.label_count,
+                if entry.pair.as_deref() == Some("test_0") {
+                    2
+                } else {
+                    1
+                }
+            );
+        }
+    }
+
+    #[rstest]
+    #[case::fail_if_not_empty(OverwritePolicy::FailIfNotEmpty)]
+    #[case::clean(OverwritePolicy::Clean)]
+    #[case::merge(OverwritePolicy::Merge)]
+    fn test_export_applies_overwrite_policy(
+        mut create_yolo_project_config: YoloProjectConfig,
+        #[case] overwrite: OverwritePolicy,
+    ) {
+        let export_source_dir = format!(
+            "{}/export_source_overwrite_{:?}",
+            TEST_SANDBOX_DIR, overwrite
+        );
+        let export_out_dir = format!("{}/export_overwrite_{:?}", TEST_SANDBOX_DIR, overwrite);
+        let _ = fs::remove_dir_all(&export_out_dir);
+
+        create_pairs(&export_source_dir, 2);
+
+        let stale_path = PathBuf::from(format!("{}/validation/images/stale.jpg", export_out_dir));
+        create_dir_and_write_file(&stale_path, "stale");
+
+        create_yolo_project_config.source_paths.images = export_source_dir.clone();
+        create_yolo_project_config.source_paths.labels = export_source_dir.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.overwrite = overwrite;
+        create_yolo_project_config.export.split = Split {
+            train: 1.0,
+            validation: 0.0,
+            test: 0.0,
+            ..Default::default()
+        };
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        let result = YoloProjectExporter::export(project);
+        let exported_image = PathBuf::from(format!("{}/train/images/test_0.jpg", export_out_dir));
+
+        match overwrite {
+            OverwritePolicy::FailIfNotEmpty => {
+                assert_eq!(
+                    result.unwrap_err().to_string(),
+                    format!("Export directory '{}' is not empty", export_out_dir)
+                );
+                assert!(stale_path.exists());
+                assert!(!exported_image.exists());
+            }
+            OverwritePolicy::Clean => {
+                result.expect("Unable to export project");
+                assert!(!stale_path.exists());
+                assert!(exported_image.exists());
+            }
+            OverwritePolicy::Merge => {
+                result.expect("Unable to export project");
+                assert!(stale_path.exists());
+                assert!(exported_image.exists());
+            }
+        }
+    }
+
+    #[rstest]
+    fn test_export_rewrites_labels_with_comments(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let export_source_dir = format!("{}/export_source_comments", TEST_SANDBOX_DIR);
+        let export_out_dir = format!("{}/export_comments", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&export_out_dir);
+
+        create_pairs(&export_source_dir, 1);
+        create_dir_and_write_file(
+            std::path::Path::new(&format!("{}/test_0.txt", export_source_dir)),
+            "0 0.5 0.5 0.2 0.2 # a comment\n\n1  0.25 0.25 0.1 0.1",
+        );
+
+        create_yolo_project_config.source_paths.images = export_source_dir.clone();
+        create_yolo_project_config.source_paths.labels = export_source_dir.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.split = Split {
+            train: 1.0,
+            validation: 0.0,
+            test: 0.0,
+            ..Default::default()
+        };
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        YoloProjectExporter::export(project.clone()).expect("Unable to export project");
+
+        assert_eq!(
+            fs::read_to_string(format!("{}/train/labels/test_0.txt", export_out_dir))
+                .expect("Unable to read exported label"),
+            "0 0.5 0.5 0.2 0.2\n1 0.25 0.25 0.1 0.1"
+        );
+
+        let mut file_list_config = create_yolo_project_config.clone();
+        file_list_config.export.layout = ExportLayout::FileLists;
+        file_list_config.export.paths.root =
+            PathBuf::from(format!("{}/export_comments_file_lists", TEST_SANDBOX_DIR));
+        let project = YoloProject::new(&file_list_config).expect("Unable to create project");
+
+        assert!(matches!(
+            YoloProjectExporter::export(project),
+            Err(ExportError::FileListLabelRewrite(_))
+        ));
+    }
+
+    #[rstest]
+    fn test_export_rewrites_labels_with_dropped_rows(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let export_source_dir = format!("{}/export_source_dropped_rows", TEST_SANDBOX_DIR);
+        let export_out_dir = format!("{}/export_dropped_rows", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&export_out_dir);
+
+        create_pairs(&export_source_dir, 1);
+        create_dir_and_write_file(
+            std::path::Path::new(&format!("{}/test_0.txt", export_source_dir)),
+            "0 0.5 0.5 0.2 0.2\n0 1.5 0.5 0.2 0.2\n1 0.25 0.25 0.1 0.1",
+        );
+
+        create_yolo_project_config.source_paths.images = export_source_dir.clone();
+        create_yolo_project_config.source_paths.labels = export_source_dir.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.row_errors = RowErrorPolicy::DropRows;
+        create_yolo_project_config.export.split = Split {
+            train: 1.0,
+            validation: 0.0,
+            test: 0.0,
+            ..Default::default()
+        };
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        assert_eq!(project.get_valid_pairs().len(), 1);
+
+        let report = YoloDataQualityReport::generate(project.clone()).expect("Expected a report");
+        assert!(report.contains("LabelDataOutOfRange"));
+
+        YoloProjectExporter::export(project).expect("Unable to export project");
+
+        assert_eq!(
+            fs::read_to_string(format!("{}/train/labels/test_0.txt", export_out_dir))
+                .expect("Unable to read exported label"),
+            "0 0.5 0.5 0.2 0.2\n1 0.25 0.25 0.1 0.1"
+        );
+    }
+
+    #[rstest]
+    fn test_file_list_export_accepts_strict_formatting_notices(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let export_source_dir = format!("{}/export_source_file_list_strict", TEST_SANDBOX_DIR);
+        let export_out_dir = format!("{}/export_file_list_strict", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&export_out_dir);
+
+        create_pairs(&export_source_dir, 1);
+        create_dir_and_write_file(
+            std::path::Path::new(&format!("{}/test_0.txt", export_source_dir)),
+            "0 0.5 0.5 0.2 0.2 ",
+        );
+
+        create_yolo_project_config.source_paths.images = export_source_dir.clone();
+        create_yolo_project_config.source_paths.labels = export_source_dir.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.layout = ExportLayout::FileLists;
+        create_yolo_project_config.export.strict_formatting = true;
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        YoloProjectExporter::export(project).expect("Unable to export project");
+    }
+
+    #[rstest]
+    fn test_file_list_export_rejects_rewritten_labels(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let export_source_dir = format!("{}/export_source_file_list_rewrite", TEST_SANDBOX_DIR);
+        let export_out_dir = format!("{}/export_file_list_rewrite", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&export_out_dir);
+
+        create_pairs(&export_source_dir, 1);
+        let label_path = format!("{}/test_0.txt", export_source_dir);
+        create_dir_and_write_file(
+            std::path::Path::new(&label_path),
+            "0 0.5 0.5 0.2 0.2\n0 1.5 0.5 0.2 0.2",
+        );
+
+        create_yolo_project_config.source_paths.images = export_source_dir.clone();
+        create_yolo_project_config.source_paths.labels = export_source_dir.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.layout = ExportLayout::FileLists;
+        create_yolo_project_config.export.row_errors = RowErrorPolicy::DropRows;
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        let error = YoloProjectExporter::export(project).expect_err("Expected an error");
+
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Label '{}' must be rewritten and cannot be exported as a file list",
+                label_path
+            )
+        );
+        assert!(!PathBuf::from(&export_out_dir).exists());
+    }
+
+    #[rstest]
+    fn test_stratified_split_spreads_background_images(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let export_source_dir = format!("{}/export_source_background", TEST_SANDBOX_DIR);
+        let export_out_dir = format!("{}/export_background", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&export_out_dir);
+
+        // Ten labelled images, five with empty labels and five without a
+        // label file.
+        create_pairs(&export_source_dir, 20);
+        for i in 10..20 {
+            let label_path = format!("{}/test_{}.txt", export_source_dir, i);
+            if i < 15 {
+                create_dir_and_write_file(std::path::Path::new(&label_path), "");
+            } else {
+                fs::remove_file(&label_path).expect("Unable to remove label");
+            }
+        }
+
+        create_yolo_project_config.source_paths.images = export_source_dir.clone();
+        create_yolo_project_config.source_paths.labels = export_source_dir.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_out_dir);
+        create_yolo_project_config.export.background = BackgroundPolicy::EmptyOrMissingLabels;
+        create_yolo_project_config.export.split = Split {
+            train: 0.6,
+            validation: 0.2,
+            test: 0.2,
+            seed: Some(7),
+            mode: SplitMode::Stratified,
+            ..Default::default()
+        };
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        assert_eq!(project.get_valid_pairs().len(), 20);
+
+        YoloProjectExporter::export(project).expect("Unable to export project");
+
+        let report: SplitReport = serde_json::from_str(
+            &fs::read_to_string(format!("{}/split_report.json", export_out_dir))
+                .expect("Unable to read split report"),
+        )
+        .expect("Unable to parse split report");
+
+        assert_eq!(report.train_images, 12);
+        assert_eq!(report.validation_images, 4);
+        assert_eq!(report.test_images, 4);
+        assert_eq!(report.train_background_images, 6);
+        assert_eq!(report.validation_background_images, 2);
+        assert_eq!(report.test_background_images, 2);
+        assert_eq!(report.background_ratio, 0.5);
+
+        // Images without a label file are exported with an empty one.
+        let labels = ["train", "validation", "test"]
+            .iter()
+            .flat_map(|split| {
+                fs::read_dir(format!("{}/{}/labels", export_out_dir, split))
+                    .expect("Unable to read labels")
+                    .map(|entry| entry.expect("Unable to read entry").path())
+            })
+            .collect::<Vec<PathBuf>>();
+
+        assert_eq!(labels.len(), 20);
+        assert_eq!(
+            labels
+                .iter()
+                .filter(|path| fs::read_to_string(path).unwrap().is_empty())
+                .count(),
+            10
+        );
+    }
 }
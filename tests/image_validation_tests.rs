@@ -0,0 +1,138 @@
+mod common;
+
+#[cfg(test)]
+mod image_validation_tests {
+    use image::{ImageBuffer, Rgb};
+    use rstest::rstest;
+    use std::{fs, path::PathBuf};
+    use yolo_io::{ImageValidation, YoloProject, YoloProjectConfig};
+
+    use crate::common::{
+        create_dir_and_write_file, create_image_file, create_yolo_project_config, image_data,
+        TEST_SANDBOX_DIR,
+    };
+
+    /// Write `image.<extension>` with a valid label and point the config
+    /// at it.
+    fn setup(
+        config: &mut YoloProjectConfig,
+        name: &str,
+        extension: &str,
+        image_data: &ImageBuffer<Rgb<u8>, Vec<u8>>,
+    ) -> PathBuf {
+        let this_test_directory = format!("{}/image_validation_{}", TEST_SANDBOX_DIR, name);
+        let _ = fs::remove_dir_all(&this_test_directory);
+
+        let image_path = PathBuf::from(format!("{}/image.{}", this_test_directory, extension));
+        create_image_file(&image_path, image_data);
+        create_dir_and_write_file(
+            &PathBuf::from(format!("{}/image.txt", this_test_directory)),
+            "0 0.5 0.5 0.5 0.5",
+        );
+
+        config.source_paths.images = this_test_directory.clone();
+        config.source_paths.labels = this_test_directory;
+
+        image_path
+    }
+
+    #[cfg(feature = "image-validation")]
+    #[rstest]
+    #[case::jpeg_header("jpeg_header", "jpg", ImageValidation::Header)]
+    #[case::jpeg_decode("jpeg_decode", "jpg", ImageValidation::Decode)]
+    #[case::png_header("png_header", "png", ImageValidation::Header)]
+    #[case::png_decode("png_decode", "png", ImageValidation::Decode)]
+    fn test_intact_images_pass_validation(
+        image_data: ImageBuffer<Rgb<u8>, Vec<u8>>,
+        mut create_yolo_project_config: YoloProjectConfig,
+        #[case] name: &str,
+        #[case] extension: &str,
+        #[case] validation: ImageValidation,
+    ) {
+        setup(
+            &mut create_yolo_project_config,
+            name,
+            extension,
+            &image_data,
+        );
+        create_yolo_project_config.export.image_validation = validation;
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        assert_eq!(project.get_valid_pairs().len(), 1);
+        assert!(project.get_invalid_pairs().is_empty());
+    }
+
+    #[cfg(feature = "image-validation")]
+    #[rstest]
+    #[case::zero_byte("zero_byte", "png", "Unreadable image: {}")]
+    #[case::truncated_jpeg("truncated_jpeg", "jpg", "Truncated image: {}")]
+    #[case::truncated_png("truncated_png", "png", "Truncated image: {}")]
+    #[case::png_named_jpg("png_named_jpg", "jpg", "Image '{}' is a PNG file")]
+    fn test_damaged_images_are_reported(
+        image_data: ImageBuffer<Rgb<u8>, Vec<u8>>,
+        mut create_yolo_project_config: YoloProjectConfig,
+        #[case] name: &str,
+        #[case] extension: &str,
+        #[case] expected: &str,
+    ) {
+        let image_path = setup(
+            &mut create_yolo_project_config,
+            name,
+            extension,
+            &image_data,
+        );
+        create_yolo_project_config.export.image_validation = ImageValidation::Header;
+
+        match name {
+            "zero_byte" => fs::write(&image_path, "").expect("Unable to write image"),
+            "png_named_jpg" => {
+                let mut png = Vec::new();
+                image_data
+                    .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+                    .expect("Unable to encode image");
+                fs::write(&image_path, png).expect("Unable to write image");
+            }
+            _ => {
+                let bytes = fs::read(&image_path).expect("Unable to read image");
+                fs::write(&image_path, &bytes[..bytes.len() / 2]).expect("Unable to write image");
+            }
+        }
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        assert!(project.get_valid_pairs().is_empty());
+        assert_eq!(
+            project
+                .get_invalid_pairs()
+                .iter()
+                .map(|error| error.to_string())
+                .collect::<Vec<String>>(),
+            vec![expected.replace("{}", &image_path.to_string_lossy())]
+        );
+    }
+
+    #[cfg(not(feature = "image-validation"))]
+    #[rstest]
+    fn test_image_validation_requires_feature(
+        image_data: ImageBuffer<Rgb<u8>, Vec<u8>>,
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        setup(
+            &mut create_yolo_project_config,
+            "unavailable",
+            "jpg",
+            &image_data,
+        );
+        create_yolo_project_config.export.image_validation = ImageValidation::Header;
+
+        let error = YoloProject::new(&create_yolo_project_config).expect_err("Expected an error");
+
+        assert_eq!(
+            error.to_string(),
+            "Image validation requires the 'image-validation' feature."
+        );
+    }
+}
@@ -5,7 +5,9 @@ mod invalid_label_tests {
     use rstest::rstest;
 
     use crate::common::TEST_SANDBOX_DIR;
-    use yolo_io::{FileMetadata, YoloClass, YoloFile, YoloFileParseError};
+    use yolo_io::{
+        BackgroundPolicy, FileMetadata, RowErrorPolicy, YoloClass, YoloFile, YoloFileParseError,
+    };
 
     fn create_yolo_classes(classes: Vec<(isize, &str)>) -> Vec<YoloClass> {
         classes
@@ -34,6 +36,7 @@ mod invalid_label_tests {
         let metadata = FileMetadata {
             classes,
             duplicate_tolerance: 0.01,
+            ..Default::default()
         };
 
         (metadata, path)
@@ -57,6 +60,7 @@ mod invalid_label_tests {
         let metadata = FileMetadata {
             classes,
             duplicate_tolerance: 0.01,
+            ..Default::default()
         };
 
         let yolo_file = YoloFile::new(&metadata, &path);
@@ -366,6 +370,7 @@ mod invalid_label_tests {
             FileMetadata {
                 classes,
                 duplicate_tolerance: tolerance,
+                ..Default::default()
             },
             path,
         )
@@ -390,4 +395,204 @@ mod invalid_label_tests {
             Err(YoloFileParseError::DuplicateEntries(_))
         ));
     }
+
+    const MIXED_ROWS: &str =
+        "0 0.5 0.5 0.2 0.2\n3 0.5 0.5 0.2 0.2\n0 1.5 0.5 0.2 0.2\n1 0.25 0.25 0.1 0.1\n0 0.5";
+
+    #[test]
+    fn test_parse_rows_collects_every_row_error() {
+        let classes = create_yolo_classes(vec![(0, "person"), (1, "car")]);
+        let (metadata, path) = create_yolo_label_file("mixed_rows.txt", classes, MIXED_ROWS);
+
+        let (yolo_file, row_errors) =
+            YoloFile::parse_rows(&metadata, &path).expect("File should be readable");
+
+        assert_eq!(
+            yolo_file
+                .entries
+                .iter()
+                .map(|entry| entry.class)
+                .collect::<Vec<isize>>(),
+            vec![0, 1]
+        );
+        assert_eq!(row_errors.len(), 3);
+        assert!(matches!(
+            row_errors[0],
+            YoloFileParseError::ClassIdNotFound(ref details) if details.row == Some(1)
+        ));
+        assert!(matches!(
+            row_errors[1],
+            YoloFileParseError::LabelDataOutOfRange(ref details) if details.row == Some(2)
+        ));
+        assert!(matches!(
+            row_errors[2],
+            YoloFileParseError::InvalidFormat(ref details) if details.row == Some(4)
+        ));
+    }
+
+    #[rstest]
+    #[case::reject(
+        RowErrorPolicy::Reject,
+        "Invalid class id '3' in file 'tests/sandbox/data/mixed_rows_policy_Reject.txt'"
+    )]
+    #[case::report_all(
+        RowErrorPolicy::ReportAll,
+        "File 'tests/sandbox/data/mixed_rows_policy_ReportAll.txt' has 3 invalid rows"
+    )]
+    fn test_yolo_file_new_applies_row_error_policy(
+        #[case] row_errors: RowErrorPolicy,
+        #[case] expected: &str,
+    ) {
+        let classes = create_yolo_classes(vec![(0, "person"), (1, "car")]);
+        let (metadata, path) = create_yolo_label_file(
+            &format!("mixed_rows_policy_{:?}.txt", row_errors),
+            classes,
+            MIXED_ROWS,
+        );
+        let metadata = FileMetadata {
+            row_errors,
+            ..metadata
+        };
+
+        let error = YoloFile::new(&metadata, &path).expect_err("Expected error");
+
+        assert_eq!(error.to_string(), expected);
+    }
+
+    #[test]
+    fn test_drop_rows_keeps_valid_rows_and_reports_dropped_ones() {
+        let classes = create_yolo_classes(vec![(0, "person"), (1, "car")]);
+        let (metadata, path) =
+            create_yolo_label_file("mixed_rows_dropped.txt", classes, MIXED_ROWS);
+        let metadata = FileMetadata {
+            row_errors: RowErrorPolicy::DropRows,
+            ..metadata
+        };
+
+        let yolo_file = YoloFile::new(&metadata, &path).expect("Valid rows should be kept");
+
+        assert_eq!(yolo_file.entries.len(), 2);
+        assert_eq!(yolo_file.warnings.len(), 3);
+        assert_eq!(
+            yolo_file.to_yolo_string(),
+            "0 0.5 0.5 0.2 0.2\n1 0.25 0.25 0.1 0.1"
+        );
+    }
+
+    #[test]
+    fn test_drop_rows_rejects_file_without_valid_rows() {
+        let classes = create_yolo_classes(vec![(0, "person")]);
+        let (metadata, path) = create_yolo_label_file(
+            "all_rows_dropped.txt",
+            classes,
+            "3 0.5 0.5 0.2 0.2\n0 1.5 0.5 0.2 0.2",
+        );
+        let metadata = FileMetadata {
+            row_errors: RowErrorPolicy::DropRows,
+            ..metadata
+        };
+
+        let result = YoloFile::new(&metadata, &path);
+
+        assert!(matches!(
+            result,
+            Err(YoloFileParseError::InvalidRows { ref rows, .. }) if rows.len() == 2
+        ));
+    }
+
+    const IRREGULAR_ROWS: &str =
+        "# person and car\n0\t0.5 0.5 0.2 0.2\n1  0.25 0.25 0.1 0.1 \r\n\n0 0.75 0.75 0.1 0.1 # occluded\n\n";
+
+    #[test]
+    fn test_yolo_file_new_tolerates_irregular_formatting() {
+        let classes = create_yolo_classes(vec![(0, "person"), (1, "car")]);
+        let (metadata, path) =
+            create_yolo_label_file("irregular_rows.txt", classes, IRREGULAR_ROWS);
+
+        let yolo_file = YoloFile::new(&metadata, &path).expect("Rows should be accepted");
+
+        assert!(yolo_file.warnings.is_empty());
+        assert_eq!(
+            yolo_file.to_yolo_string(),
+            "0 0.5 0.5 0.2 0.2\n1 0.25 0.25 0.1 0.1\n0 0.75 0.75 0.1 0.1"
+        );
+    }
+
+    #[test]
+    fn test_yolo_file_new_records_irregular_formatting() {
+        let classes = create_yolo_classes(vec![(0, "person"), (1, "car")]);
+        let (metadata, path) =
+            create_yolo_label_file("irregular_rows_strict.txt", classes, IRREGULAR_ROWS);
+
+        let yolo_file = YoloFile::new(&metadata, &path).expect("Rows should be accepted");
+
+        assert_eq!(yolo_file.entries.len(), 3);
+        assert!(yolo_file.warnings.is_empty());
+        assert_eq!(
+            yolo_file
+                .formatting
+                .iter()
+                .map(|warning| warning.to_string())
+                .collect::<Vec<String>>(),
+            vec![
+                "Comment in file 'tests/sandbox/data/irregular_rows_strict.txt' on line 0",
+                "Irregular whitespace in file 'tests/sandbox/data/irregular_rows_strict.txt' on line 1",
+                "Irregular whitespace in file 'tests/sandbox/data/irregular_rows_strict.txt' on line 2",
+                "Blank line in file 'tests/sandbox/data/irregular_rows_strict.txt' on line 3",
+                "Comment in file 'tests/sandbox/data/irregular_rows_strict.txt' on line 4",
+                "Blank line in file 'tests/sandbox/data/irregular_rows_strict.txt' on line 5",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_yolo_file_new_records_crlf_line_endings() {
+        let classes = create_yolo_classes(vec![(0, "person"), (1, "car")]);
+        let (metadata, path) = create_yolo_label_file(
+            "crlf_rows.txt",
+            classes,
+            "0 0.5 0.5 0.2 0.2\r\n1 0.25 0.25 0.1 0.1\r\n",
+        );
+
+        let yolo_file = YoloFile::new(&metadata, &path).expect("Rows should be accepted");
+
+        assert_eq!(yolo_file.entries.len(), 2);
+        assert_eq!(
+            yolo_file
+                .formatting
+                .iter()
+                .map(|warning| warning.to_string())
+                .collect::<Vec<String>>(),
+            vec![
+                "Irregular whitespace in file 'tests/sandbox/data/crlf_rows.txt' on line 0",
+                "Irregular whitespace in file 'tests/sandbox/data/crlf_rows.txt' on line 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_yolo_file_new_rejects_file_with_only_comments() {
+        let classes = create_yolo_classes(vec![(0, "person")]);
+        let (metadata, path) =
+            create_yolo_label_file("only_comments.txt", classes, "# nothing here\n\n");
+
+        let result = YoloFile::new(&metadata, &path);
+
+        assert!(matches!(result, Err(YoloFileParseError::EmptyFile(_))));
+    }
+
+    #[test]
+    fn test_yolo_file_new_accepts_empty_file_as_background() {
+        let classes = create_yolo_classes(vec![(0, "person")]);
+        let (metadata, path) = create_yolo_label_file("background.txt", classes, "");
+        let metadata = FileMetadata {
+            background: BackgroundPolicy::EmptyLabels,
+            ..metadata
+        };
+
+        let yolo_file = YoloFile::new(&metadata, &path).expect("Empty file should be accepted");
+
+        assert!(yolo_file.is_background());
+        assert_eq!(yolo_file.to_yolo_string(), "");
+    }
 }
@@ -0,0 +1,177 @@
+mod common;
+
+#[cfg(test)]
+mod obb_tests {
+    use std::{fs, path::PathBuf};
+
+    use image::{ImageBuffer, Rgb};
+    use rstest::rstest;
+    use yolo_io::{
+        FileMetadata, Split, YoloClass, YoloFile, YoloFileParseError, YoloProject,
+        YoloProjectConfig, YoloProjectExporter,
+    };
+
+    use crate::common::{
+        create_dir_and_write_file, create_image_file, create_yolo_project_config, image_data,
+        TEST_SANDBOX_DIR,
+    };
+
+    fn create_obb_label_file(
+        filename: &str,
+        content: &str,
+        tolerance: f32,
+    ) -> (FileMetadata, String) {
+        let dir = format!("{}/obb", TEST_SANDBOX_DIR);
+        let path = format!("{}/{}", dir, filename);
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, content).unwrap();
+
+        let metadata = FileMetadata {
+            classes: vec![YoloClass {
+                id: 0,
+                name: "plane".to_string(),
+            }],
+            duplicate_tolerance: tolerance,
+            oriented_bounding_boxes: true,
+            ..Default::default()
+        };
+
+        (metadata, path)
+    }
+
+    #[test]
+    fn test_yolo_file_new_parses_oriented_boxes() {
+        let (metadata, path) = create_obb_label_file(
+            "obb_valid.txt",
+            "0 0.5 0.2 0.8 0.5 0.5 0.8 0.2 0.5\n0 0.1 0.1 0.3 0.1 0.3 0.2 0.1 0.2",
+            0.05,
+        );
+
+        let yolo_file = YoloFile::new(&metadata, &path).expect("OBB file should parse");
+
+        assert!(yolo_file.polygons.is_empty());
+        assert_eq!(yolo_file.oriented_boxes.len(), 2);
+        assert_eq!(
+            yolo_file.oriented_boxes[0].corners,
+            [(0.5, 0.2), (0.8, 0.5), (0.5, 0.8), (0.2, 0.5)]
+        );
+    }
+
+    #[test]
+    fn test_yolo_file_new_rejects_crossed_corners() {
+        let (metadata, path) =
+            create_obb_label_file("obb_order.txt", "0 0.1 0.1 0.3 0.2 0.3 0.1 0.1 0.2", 0.05);
+
+        let yolo_file = YoloFile::new(&metadata, &path);
+
+        if let Err(err) = yolo_file {
+            assert!(matches!(err, YoloFileParseError::InvalidCornerOrder(_)));
+            assert_eq!(
+                err.to_string(),
+                "Oriented box corners in file 'tests/sandbox/obb/obb_order.txt' on line 0 are not in order around the box"
+            );
+        } else {
+            panic!("Expected error");
+        }
+    }
+
+    #[test]
+    fn test_yolo_file_new_rejects_concave_box() {
+        let (metadata, path) =
+            create_obb_label_file("obb_concave.txt", "0 0.1 0.1 0.5 0.3 0.9 0.1 0.5 0.9", 0.05);
+
+        let yolo_file = YoloFile::new(&metadata, &path);
+
+        assert!(matches!(
+            yolo_file,
+            Err(YoloFileParseError::NonConvexBox(_))
+        ));
+    }
+
+    #[test]
+    fn test_yolo_file_new_rejects_wrong_column_count() {
+        let (metadata, path) = create_obb_label_file("obb_columns.txt", "0 0.5 0.5 0.2 0.2", 0.05);
+
+        let yolo_file = YoloFile::new(&metadata, &path);
+
+        assert!(matches!(
+            yolo_file,
+            Err(YoloFileParseError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_yolo_file_new_detects_rotated_duplicates() {
+        // The second box lists the same rotated corners starting from another vertex.
+        let (metadata, path) = create_obb_label_file(
+            "obb_duplicate.txt",
+            "0 0.5 0.2 0.8 0.5 0.5 0.8 0.2 0.5\n0 0.8 0.5 0.5 0.8 0.2 0.5 0.5 0.2",
+            0.05,
+        );
+
+        let yolo_file = YoloFile::new(&metadata, &path);
+
+        assert!(matches!(
+            yolo_file,
+            Err(YoloFileParseError::DuplicateEntries(_))
+        ));
+    }
+
+    #[test]
+    fn test_yolo_file_new_allows_partially_overlapping_boxes() {
+        let (metadata, path) = create_obb_label_file(
+            "obb_overlap.txt",
+            "0 0.5 0.2 0.8 0.5 0.5 0.8 0.2 0.5\n0 0.6 0.2 0.9 0.5 0.6 0.8 0.3 0.5",
+            0.05,
+        );
+
+        let yolo_file = YoloFile::new(&metadata, &path);
+
+        assert!(yolo_file.is_ok());
+    }
+
+    #[rstest]
+    fn test_oriented_boxes_round_trip_through_export(
+        image_data: ImageBuffer<Rgb<u8>, Vec<u8>>,
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let this_test_directory = format!("{}/obb_project", TEST_SANDBOX_DIR);
+        let export_directory = format!("{}/obb_export", TEST_SANDBOX_DIR);
+
+        let _ = fs::remove_dir_all(&this_test_directory);
+        let _ = fs::remove_dir_all(&export_directory);
+
+        let image_file = PathBuf::from(format!("{}/plane.jpg", this_test_directory));
+        create_image_file(&image_file, &image_data);
+
+        let label_file = PathBuf::from(format!("{}/plane.txt", this_test_directory));
+        create_dir_and_write_file(&label_file, "0 0.5 0.2 0.8 0.5 0.5 0.8 0.2 0.5");
+
+        create_yolo_project_config.source_paths.images = this_test_directory.clone();
+        create_yolo_project_config.source_paths.labels = this_test_directory.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_directory);
+        create_yolo_project_config.export.oriented_bounding_boxes = true;
+        create_yolo_project_config.export.split = Split {
+            train: 1.0,
+            validation: 0.0,
+            test: 0.0,
+            ..Default::default()
+        };
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+        let original = project.get_pair("plane").unwrap().label_file.unwrap();
+
+        YoloProjectExporter::export(project).expect("Unable to export project");
+
+        create_yolo_project_config.source_paths.images = export_directory.clone();
+        create_yolo_project_config.source_paths.labels = export_directory.clone();
+
+        let reloaded =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to reload export");
+        let exported = reloaded.get_pair("plane").unwrap().label_file.unwrap();
+
+        assert_eq!(exported.oriented_boxes, original.oriented_boxes);
+    }
+}
@@ -6,7 +6,7 @@ mod pairing_tests {
     use image::{ImageBuffer, Rgb};
     use rstest::rstest;
     use std::{fs, path::PathBuf};
-    use yolo_io::{YoloProject, YoloProjectConfig};
+    use yolo_io::{BackgroundPolicy, PairingError, YoloProject, YoloProjectConfig};
 
     use crate::common::{
         create_dir_and_write_file, create_image_file, create_yolo_project_config, image_data,
@@ -238,4 +238,139 @@ mod pairing_tests {
 
         assert!(pair.is_none());
     }
+
+    #[rstest]
+    fn test_nested_directories_load_in_deterministic_order(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let this_test_directory = format!("{}/nested_order", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&this_test_directory);
+
+        let mut expected = Vec::<String>::new();
+        for directory in ["c", "a", "b"] {
+            for i in (0..10).rev() {
+                let stem = format!("{}_{}", directory, i);
+                let base = format!("{}/{}/{}/{}", this_test_directory, directory, i % 3, stem);
+
+                create_dir_and_write_file(&PathBuf::from(format!("{}.jpg", base)), "");
+                create_dir_and_write_file(
+                    &PathBuf::from(format!("{}.txt", base)),
+                    "0 0.5 0.5 0.5 0.5",
+                );
+                expected.push(stem);
+            }
+        }
+        expected.sort();
+
+        create_yolo_project_config.source_paths.images = this_test_directory.clone();
+        create_yolo_project_config.source_paths.labels = this_test_directory.clone();
+
+        let first = YoloProject::new(&create_yolo_project_config)
+            .expect("Unable to create project")
+            .get_valid_pairs();
+        let second = YoloProject::new(&create_yolo_project_config)
+            .expect("Unable to create project")
+            .get_valid_pairs();
+
+        let names = first
+            .iter()
+            .map(|pair| pair.name.clone())
+            .collect::<Vec<String>>();
+
+        assert_eq!(names, expected);
+        assert_eq!(first, second);
+    }
+
+    #[rstest]
+    #[ignore = "writes 100,000 files; run with `cargo test -- --ignored`"]
+    fn test_pairing_scales_linearly_with_file_count(
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        // 50,000 images and 50,000 labels. Matching every stem against
+        // every file takes minutes at this size; indexed pairing takes
+        // well under the limit below even in debug builds.
+        const NUM_PAIRS: usize = 50_000;
+        const TIME_LIMIT: std::time::Duration = std::time::Duration::from_secs(60);
+
+        let this_test_directory = format!("{}/pairing_scale", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&this_test_directory);
+
+        for i in 0..NUM_PAIRS {
+            let base = format!("{}/{}/pair_{}", this_test_directory, i % 100, i);
+            create_dir_and_write_file(&PathBuf::from(format!("{}.jpg", base)), "");
+            create_dir_and_write_file(&PathBuf::from(format!("{}.txt", base)), "0 0.5 0.5 0.5 0.5");
+        }
+
+        create_yolo_project_config.source_paths.images = this_test_directory.clone();
+        create_yolo_project_config.source_paths.labels = this_test_directory.clone();
+
+        let started = std::time::Instant::now();
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+        let elapsed = started.elapsed();
+
+        assert_eq!(project.get_valid_pairs().len(), NUM_PAIRS);
+        assert!(
+            elapsed < TIME_LIMIT,
+            "Loading {} pairs took {:?}",
+            NUM_PAIRS,
+            elapsed
+        );
+
+        let _ = fs::remove_dir_all(&this_test_directory);
+    }
+
+    #[rstest]
+    #[case::reject(BackgroundPolicy::Reject, &["labelled"])]
+    #[case::empty_labels(BackgroundPolicy::EmptyLabels, &["empty", "labelled"])]
+    #[case::empty_or_missing_labels(
+        BackgroundPolicy::EmptyOrMissingLabels,
+        &["empty", "labelled", "unlabelled"]
+    )]
+    fn test_project_keeps_background_images_by_policy(
+        mut create_yolo_project_config: YoloProjectConfig,
+        #[case] background: BackgroundPolicy,
+        #[case] expected: &[&str],
+    ) {
+        let this_test_directory = format!("{}/background_{:?}", TEST_SANDBOX_DIR, background);
+        let _ = fs::remove_dir_all(&this_test_directory);
+
+        for stem in ["labelled", "empty", "unlabelled"] {
+            create_dir_and_write_file(
+                &PathBuf::from(format!("{}/{}.jpg", this_test_directory, stem)),
+                "",
+            );
+        }
+        create_dir_and_write_file(
+            &PathBuf::from(format!("{}/labelled.txt", this_test_directory)),
+            "0 0.5 0.5 0.5 0.5",
+        );
+        create_dir_and_write_file(
+            &PathBuf::from(format!("{}/empty.txt", this_test_directory)),
+            "",
+        );
+
+        create_yolo_project_config.source_paths.images = this_test_directory.clone();
+        create_yolo_project_config.source_paths.labels = this_test_directory.clone();
+        create_yolo_project_config.export.background = background;
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        let mut names = project
+            .get_valid_pairs()
+            .iter()
+            .map(|pair| pair.name.clone())
+            .collect::<Vec<String>>();
+        names.sort();
+
+        assert_eq!(names, expected);
+        assert_eq!(
+            project
+                .get_invalid_pairs()
+                .iter()
+                .any(|error| matches!(error, PairingError::LabelFileMissing(_))),
+            background != BackgroundPolicy::EmptyOrMissingLabels
+        );
+    }
 }
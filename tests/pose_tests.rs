@@ -0,0 +1,247 @@
+mod common;
+
+#[cfg(test)]
+mod pose_tests {
+    use std::{fs, path::PathBuf};
+
+    use image::{ImageBuffer, Rgb};
+    use rstest::rstest;
+    use yolo_io::{
+        FileMetadata, Keypoints, YoloClass, YoloFile, YoloFileParseError, YoloKeypoint,
+        YoloProject, YoloProjectConfig, YoloProjectExporter,
+    };
+
+    use crate::common::{
+        create_dir_and_write_file, create_image_file, create_yolo_project_config, image_data,
+        TEST_SANDBOX_DIR,
+    };
+
+    fn create_pose_label_file(
+        filename: &str,
+        content: &str,
+        keypoints: Keypoints,
+    ) -> (FileMetadata, String) {
+        let dir = format!("{}/pose", TEST_SANDBOX_DIR);
+        let path = format!("{}/{}", dir, filename);
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, content).unwrap();
+
+        let metadata = FileMetadata {
+            classes: vec![YoloClass {
+                id: 0,
+                name: "person".to_string(),
+            }],
+            duplicate_tolerance: 0.01,
+            keypoints: Some(keypoints),
+            ..Default::default()
+        };
+
+        (metadata, path)
+    }
+
+    fn three_visible_keypoints() -> Keypoints {
+        Keypoints {
+            count: 3,
+            visibility: true,
+            flip_idx: Some(vec![0, 2, 1]),
+        }
+    }
+
+    #[test]
+    fn test_yolo_file_new_parses_pose_rows() {
+        let (metadata, path) = create_pose_label_file(
+            "pose_valid.txt",
+            "0 0.5 0.5 0.4 0.4 0.5 0.4 2 0.45 0.5 1 0.55 0.5 0",
+            three_visible_keypoints(),
+        );
+
+        let yolo_file = YoloFile::new(&metadata, &path).expect("Pose file should parse");
+
+        assert!(yolo_file.entries.is_empty());
+        assert_eq!(yolo_file.poses.len(), 1);
+        assert_eq!(yolo_file.poses[0].entry.width, 0.4);
+        assert_eq!(
+            yolo_file.poses[0].keypoints,
+            vec![
+                YoloKeypoint {
+                    x: 0.5,
+                    y: 0.4,
+                    visibility: Some(2),
+                },
+                YoloKeypoint {
+                    x: 0.45,
+                    y: 0.5,
+                    visibility: Some(1),
+                },
+                YoloKeypoint {
+                    x: 0.55,
+                    y: 0.5,
+                    visibility: Some(0),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_yolo_file_new_parses_pose_rows_without_visibility() {
+        let keypoints = Keypoints {
+            count: 2,
+            visibility: false,
+            flip_idx: None,
+        };
+        let (metadata, path) = create_pose_label_file(
+            "pose_no_vis.txt",
+            "0 0.5 0.5 0.4 0.4 0.5 0.4 0.45 0.5",
+            keypoints,
+        );
+
+        let yolo_file = YoloFile::new(&metadata, &path).expect("Pose file should parse");
+
+        assert_eq!(yolo_file.poses[0].keypoints[1].visibility, None);
+    }
+
+    #[test]
+    fn test_yolo_file_new_rejects_wrong_keypoint_count() {
+        let (metadata, path) = create_pose_label_file(
+            "pose_count.txt",
+            "0 0.5 0.5 0.4 0.4 0.5 0.4 2 0.45 0.5 1",
+            three_visible_keypoints(),
+        );
+
+        let yolo_file = YoloFile::new(&metadata, &path);
+
+        assert!(matches!(
+            yolo_file,
+            Err(YoloFileParseError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_yolo_file_new_rejects_keypoint_out_of_range() {
+        let (metadata, path) = create_pose_label_file(
+            "pose_range.txt",
+            "0 0.5 0.5 0.4 0.4 0.5 0.4 2 1.45 0.5 1 0.55 0.5 0",
+            three_visible_keypoints(),
+        );
+
+        let yolo_file = YoloFile::new(&metadata, &path);
+
+        if let Err(err) = yolo_file {
+            assert_eq!(
+                err.to_string(),
+                "Invalid data value for 'px2' in file 'tests/sandbox/pose/pose_range.txt' on line 0.  Value is '1.45'"
+            );
+        } else {
+            panic!("Expected error");
+        }
+    }
+
+    #[test]
+    fn test_yolo_file_new_rejects_invalid_visibility_flag() {
+        let (metadata, path) = create_pose_label_file(
+            "pose_visibility.txt",
+            "0 0.5 0.5 0.4 0.4 0.5 0.4 3 0.45 0.5 1 0.55 0.5 0",
+            three_visible_keypoints(),
+        );
+
+        let yolo_file = YoloFile::new(&metadata, &path);
+
+        if let Err(err) = yolo_file {
+            assert_eq!(
+                err.to_string(),
+                "Invalid data value for 'v1' in file 'tests/sandbox/pose/pose_visibility.txt' on line 0.  Value is '3'"
+            );
+        } else {
+            panic!("Expected error");
+        }
+    }
+
+    #[rstest]
+    fn test_export_writes_keypoint_shape(
+        image_data: ImageBuffer<Rgb<u8>, Vec<u8>>,
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let this_test_directory = format!("{}/pose_project", TEST_SANDBOX_DIR);
+        let export_directory = format!("{}/pose_export", TEST_SANDBOX_DIR);
+
+        let _ = fs::remove_dir_all(&this_test_directory);
+        let _ = fs::remove_dir_all(&export_directory);
+
+        let image_file = PathBuf::from(format!("{}/pose.jpg", this_test_directory));
+        create_image_file(&image_file, &image_data);
+
+        let label_file = PathBuf::from(format!("{}/pose.txt", this_test_directory));
+        create_dir_and_write_file(
+            &label_file,
+            "0 0.5 0.5 0.4 0.4 0.5 0.4 2 0.45 0.5 1 0.55 0.5 0",
+        );
+
+        create_yolo_project_config.source_paths.images = this_test_directory.clone();
+        create_yolo_project_config.source_paths.labels = this_test_directory.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_directory);
+        create_yolo_project_config.export.keypoints = Some(three_visible_keypoints());
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        assert!(project.get_pair("pose").is_some());
+
+        YoloProjectExporter::export(project).expect("Unable to export project");
+
+        let yolo_yaml = fs::read_to_string(format!("{}/test_project.yaml", export_directory))
+            .expect("Unable to read yaml");
+
+        let expected_yaml = r#"# Generate by yolo_io - https://github.com/Ladvien/yolo_io
+path: tests/sandbox/pose_export
+train: train/
+val: validation/
+test: test/
+
+kpt_shape: [3, 3]
+flip_idx: [0, 2, 1]
+
+names:
+  0: person
+  1: car
+"#;
+
+        assert_eq!(yolo_yaml, expected_yaml);
+    }
+
+    #[rstest]
+    #[case::wrong_length(vec![0, 2], "flip_idx has 2 entries but there are 3 keypoints")]
+    #[case::out_of_range(vec![0, 5, 1], "flip_idx entry 5 is not below the keypoint count 3")]
+    fn test_export_rejects_invalid_flip_idx(
+        image_data: ImageBuffer<Rgb<u8>, Vec<u8>>,
+        mut create_yolo_project_config: YoloProjectConfig,
+        #[case] flip_idx: Vec<usize>,
+        #[case] expected: &str,
+    ) {
+        let this_test_directory = format!("{}/pose_flip_idx", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&this_test_directory);
+
+        create_image_file(
+            &PathBuf::from(format!("{}/pose.jpg", this_test_directory)),
+            &image_data,
+        );
+        create_dir_and_write_file(
+            &PathBuf::from(format!("{}/pose.txt", this_test_directory)),
+            "0 0.5 0.5 0.4 0.4 0.5 0.4 2 0.45 0.5 1 0.55 0.5 0",
+        );
+
+        create_yolo_project_config.source_paths.images = this_test_directory.clone();
+        create_yolo_project_config.source_paths.labels = this_test_directory.clone();
+        create_yolo_project_config.export.keypoints = Some(Keypoints {
+            flip_idx: Some(flip_idx),
+            ..three_visible_keypoints()
+        });
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        let error = YoloProjectExporter::plan(&project).expect_err("Expected an error");
+
+        assert_eq!(error.to_string(), expected);
+    }
+}
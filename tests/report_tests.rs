@@ -9,8 +9,8 @@ mod report_tests {
 
     use yolo_io::{
         DataQualityItem, DuplicateImageLabelPair, ImageLabelPair, PairingError, PairingResult,
-        YoloDataQualityReport, YoloFileParseError, YoloFileParseErrorDetails, YoloProject,
-        YoloProjectData,
+        YoloDataQualityReport, YoloFile, YoloFileParseError, YoloFileParseErrorDetails,
+        YoloProject, YoloProjectData,
     };
 
     fn create_test_project(pairs: Vec<PairingResult>) -> YoloProject {
@@ -158,4 +158,73 @@ mod report_tests {
 
         assert_eq!(report, expected);
     }
+
+    #[test]
+    fn test_generate_report_lists_every_invalid_row() {
+        let row_error = |row: usize| {
+            YoloFileParseError::InvalidFormat(YoloFileParseErrorDetails {
+                path: "label.txt".to_string(),
+                class: None,
+                row: Some(row),
+                other_row: None,
+                column: None,
+                value: None,
+            })
+        };
+        let pairing_error = PairingError::LabelFileError(YoloFileParseError::InvalidRows {
+            path: "label.txt".to_string(),
+            rows: vec![row_error(2), row_error(5)],
+        });
+        let project = create_test_project(vec![PairingResult::Invalid(pairing_error)]);
+
+        let report = YoloDataQualityReport::generate(project).unwrap();
+        let expected = serde_json::to_string(
+            &[row_error(2), row_error(5)]
+                .into_iter()
+                .map(|error| {
+                    let pairing_error = PairingError::LabelFileError(error);
+                    DataQualityItem {
+                        source: "YoloFileParseError::InvalidFormat".to_string(),
+                        message: pairing_error.to_string(),
+                        data: pairing_error,
+                    }
+                })
+                .collect::<Vec<DataQualityItem>>(),
+        )
+        .unwrap();
+
+        assert_eq!(report, expected);
+    }
+
+    #[rstest]
+    #[case::lenient(false, 0)]
+    #[case::strict(true, 1)]
+    fn test_generate_report_lists_formatting_only_when_strict(
+        #[case] strict_formatting: bool,
+        #[case] expected: usize,
+    ) {
+        let comment = YoloFileParseError::Comment(YoloFileParseErrorDetails {
+            path: "label.txt".to_string(),
+            class: None,
+            row: Some(0),
+            other_row: None,
+            column: None,
+            value: None,
+        });
+        let mut label_file = YoloFile::background("label");
+        label_file.formatting.push(comment);
+
+        let mut project = create_test_project(vec![PairingResult::Valid(ImageLabelPair {
+            name: "label".to_string(),
+            image_path: Some(PathBuf::from("label.jpg")),
+            label_file: Some(label_file),
+        })]);
+        project.config.export.strict_formatting = strict_formatting;
+
+        let items = YoloDataQualityReport::generate(project)
+            .map(|report| serde_json::from_str::<Vec<DataQualityItem>>(&report).unwrap())
+            .unwrap_or_default();
+
+        assert_eq!(items.len(), expected);
+    }
 }
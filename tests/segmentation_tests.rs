@@ -0,0 +1,163 @@
+mod common;
+
+#[cfg(test)]
+mod segmentation_tests {
+    use std::{fs, path::PathBuf};
+
+    use image::{ImageBuffer, Rgb};
+    use rstest::rstest;
+    use yolo_io::{
+        FileMetadata, Split, YoloClass, YoloFile, YoloFileParseError, YoloPolygon, YoloProject,
+        YoloProjectConfig, YoloProjectExporter,
+    };
+
+    use crate::common::{
+        create_dir_and_write_file, create_image_file, create_yolo_project_config, image_data,
+        TEST_SANDBOX_DIR,
+    };
+
+    fn create_polygon_label_file(filename: &str, content: &str) -> (FileMetadata, String) {
+        let dir = format!("{}/segmentation", TEST_SANDBOX_DIR);
+        let path = format!("{}/{}", dir, filename);
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, content).unwrap();
+
+        let metadata = FileMetadata {
+            classes: vec![
+                YoloClass {
+                    id: 0,
+                    name: "person".to_string(),
+                },
+                YoloClass {
+                    id: 1,
+                    name: "car".to_string(),
+                },
+            ],
+            duplicate_tolerance: 0.01,
+            ..Default::default()
+        };
+
+        (metadata, path)
+    }
+
+    #[test]
+    fn test_yolo_file_new_parses_polygons() {
+        let (metadata, path) = create_polygon_label_file(
+            "polygon_valid.txt",
+            "0 0.1 0.1 0.5 0.1 0.3 0.4\n1 0.5 0.5 0.25 0.25\n1 0.6 0.6 0.9 0.6 0.9 0.9 0.6 0.9",
+        );
+
+        let yolo_file = YoloFile::new(&metadata, &path).expect("Polygon file should parse");
+
+        assert_eq!(yolo_file.entries.len(), 1);
+        assert_eq!(
+            yolo_file.polygons,
+            vec![
+                YoloPolygon {
+                    class: 0,
+                    points: vec![(0.1, 0.1), (0.5, 0.1), (0.3, 0.4)],
+                },
+                YoloPolygon {
+                    class: 1,
+                    points: vec![(0.6, 0.6), (0.9, 0.6), (0.9, 0.9), (0.6, 0.9)],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_yolo_file_new_rejects_odd_polygon_coordinates() {
+        let (metadata, path) =
+            create_polygon_label_file("polygon_odd.txt", "0 0.1 0.1 0.5 0.1 0.3 0.4 0.2");
+
+        let yolo_file = YoloFile::new(&metadata, &path);
+
+        if let Err(err) = yolo_file {
+            assert!(matches!(err, YoloFileParseError::OddPolygonCoordinates(_)));
+            assert_eq!(
+                err.to_string(),
+                "Polygon in file 'tests/sandbox/segmentation/polygon_odd.txt' on line 0 has an odd number of coordinates"
+            );
+        } else {
+            panic!("Expected error");
+        }
+    }
+
+    #[test]
+    fn test_yolo_file_new_rejects_polygon_point_out_of_range() {
+        let (metadata, path) =
+            create_polygon_label_file("polygon_range.txt", "0 0.1 0.1 0.5 1.1 0.3 0.4");
+
+        let yolo_file = YoloFile::new(&metadata, &path);
+
+        if let Err(err) = yolo_file {
+            assert_eq!(
+                err.to_string(),
+                "Invalid data value for 'y2' in file 'tests/sandbox/segmentation/polygon_range.txt' on line 0.  Value is '1.1'"
+            );
+        } else {
+            panic!("Expected error");
+        }
+    }
+
+    #[test]
+    fn test_yolo_file_new_rejects_duplicate_polygons() {
+        let (metadata, path) = create_polygon_label_file(
+            "polygon_duplicate.txt",
+            "0 0.1 0.1 0.5 0.1 0.3 0.4\n0 0.1 0.1 0.5 0.1 0.3 0.405",
+        );
+
+        let yolo_file = YoloFile::new(&metadata, &path);
+
+        assert!(matches!(
+            yolo_file,
+            Err(YoloFileParseError::DuplicateEntries(_))
+        ));
+    }
+
+    #[rstest]
+    fn test_polygon_labels_are_paired_and_exported_unchanged(
+        image_data: ImageBuffer<Rgb<u8>, Vec<u8>>,
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let this_test_directory = format!("{}/segmentation_project", TEST_SANDBOX_DIR);
+        let export_directory = format!("{}/segmentation_export", TEST_SANDBOX_DIR);
+
+        let _ = fs::remove_dir_all(&this_test_directory);
+        let _ = fs::remove_dir_all(&export_directory);
+
+        let label_content = "0 0.1 0.1 0.5 0.1 0.3 0.4";
+
+        let image_file = PathBuf::from(format!("{}/poly.jpg", this_test_directory));
+        create_image_file(&image_file, &image_data);
+
+        let label_file = PathBuf::from(format!("{}/poly.txt", this_test_directory));
+        create_dir_and_write_file(&label_file, label_content);
+
+        create_yolo_project_config.source_paths.images = this_test_directory.clone();
+        create_yolo_project_config.source_paths.labels = this_test_directory.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_directory);
+        create_yolo_project_config.export.split = Split {
+            train: 1.0,
+            validation: 0.0,
+            test: 0.0,
+            ..Default::default()
+        };
+
+        let project =
+            YoloProject::new(&create_yolo_project_config).expect("Unable to create project");
+
+        let pair = project
+            .get_pair("poly")
+            .expect("Polygon pair should be valid");
+        assert_eq!(pair.label_file.unwrap().polygons.len(), 1);
+
+        YoloProjectExporter::export(project).expect("Unable to export project");
+
+        let exported = fs::read_to_string(format!("{}/train/labels/poly.txt", export_directory))
+            .expect("Unable to read exported label");
+
+        assert_eq!(exported, label_content);
+    }
+}
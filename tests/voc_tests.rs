@@ -0,0 +1,383 @@
+mod common;
+
+#[cfg(test)]
+mod voc_tests {
+    use std::{fs, path::PathBuf};
+
+    use image::{ImageBuffer, Rgb};
+    use rstest::rstest;
+    use yolo_io::{
+        FileMetadata, ImageLabelPair, LabelSource, LinkMode, OverwritePolicy, PairingError,
+        RowErrorPolicy, Split, VocExporter, YoloClass, YoloEntry, YoloFile, YoloFileParseError,
+        YoloKeypoint, YoloOrientedBox, YoloPolygon, YoloPose, YoloProject, YoloProjectConfig,
+        YoloProjectExporter,
+    };
+
+    use crate::common::{
+        create_dir_and_write_file, create_image_file, create_yolo_project_config, image_data,
+        TEST_SANDBOX_DIR,
+    };
+
+    const VOC_XML: &str = r#"<annotation>
+  <folder>images</folder>
+  <filename>street.jpg</filename>
+  <size>
+    <width>800</width>
+    <height>400</height>
+    <depth>3</depth>
+  </size>
+  <object>
+    <name>person</name>
+    <pose>Unspecified</pose>
+    <truncated>0</truncated>
+    <difficult>0</difficult>
+    <bndbox>
+      <xmin>100</xmin>
+      <ymin>100</ymin>
+      <xmax>300</xmax>
+      <ymax>200</ymax>
+    </bndbox>
+  </object>
+  <object>
+    <name>car</name>
+    <bndbox>
+      <xmin>400</xmin>
+      <ymin>200</ymin>
+      <xmax>800</xmax>
+      <ymax>400</ymax>
+    </bndbox>
+  </object>
+</annotation>"#;
+
+    fn create_voc_file(filename: &str, content: &str) -> (FileMetadata, String) {
+        let dir = format!("{}/voc", TEST_SANDBOX_DIR);
+        let path = format!("{}/{}", dir, filename);
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, content).unwrap();
+
+        let metadata = FileMetadata {
+            classes: vec![
+                YoloClass {
+                    id: 0,
+                    name: "person".to_string(),
+                },
+                YoloClass {
+                    id: 1,
+                    name: "car".to_string(),
+                },
+            ],
+            duplicate_tolerance: 0.01,
+            ..Default::default()
+        };
+
+        (metadata, path)
+    }
+
+    #[test]
+    fn test_yolo_file_from_voc_normalizes_boxes() {
+        let (metadata, path) = create_voc_file("street.xml", VOC_XML);
+
+        let yolo_file = YoloFile::from_voc(&metadata, &path).expect("VOC file should parse");
+
+        assert_eq!(yolo_file.stem, "street");
+        assert_eq!(yolo_file.source, LabelSource::Voc);
+        assert_eq!(
+            yolo_file.entries,
+            vec![
+                YoloEntry {
+                    class: 0,
+                    x_center: 0.25,
+                    y_center: 0.375,
+                    width: 0.25,
+                    height: 0.25,
+                },
+                YoloEntry {
+                    class: 1,
+                    x_center: 0.75,
+                    y_center: 0.75,
+                    width: 0.5,
+                    height: 0.5,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_yolo_file_from_voc_rejects_unknown_class_name() {
+        let (metadata, path) =
+            create_voc_file("unknown.xml", &VOC_XML.replace("<name>car", "<name>bus"));
+
+        let yolo_file = YoloFile::from_voc(&metadata, &path);
+
+        if let Err(err) = yolo_file {
+            assert!(matches!(err, YoloFileParseError::ClassIdNotFound(_)));
+            assert_eq!(
+                err.to_string(),
+                "Invalid class id 'bus' in file 'tests/sandbox/voc/unknown.xml'"
+            );
+        } else {
+            panic!("Expected error");
+        }
+    }
+
+    #[test]
+    fn test_yolo_file_from_voc_rejects_box_outside_image() {
+        let (metadata, path) = create_voc_file(
+            "outside.xml",
+            &VOC_XML.replace("<xmax>800</xmax>", "<xmax>801</xmax>"),
+        );
+
+        let yolo_file = YoloFile::from_voc(&metadata, &path);
+
+        if let Err(err) = yolo_file {
+            assert_eq!(
+                err.to_string(),
+                "Invalid data value for 'xmax' in file 'tests/sandbox/voc/outside.xml' on line 1.  Value is '801'"
+            );
+        } else {
+            panic!("Expected error");
+        }
+    }
+
+    #[test]
+    fn test_yolo_file_from_voc_rejects_malformed_xml() {
+        let (metadata, path) = create_voc_file("malformed.xml", "<annotation><size>");
+
+        let yolo_file = YoloFile::from_voc(&metadata, &path);
+
+        assert!(matches!(
+            yolo_file,
+            Err(YoloFileParseError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_yolo_file_from_voc_drops_invalid_objects() {
+        let (metadata, path) =
+            create_voc_file("dropped.xml", &VOC_XML.replace("<name>car", "<name>bus"));
+        let metadata = FileMetadata {
+            row_errors: RowErrorPolicy::DropRows,
+            ..metadata
+        };
+
+        let yolo_file = YoloFile::from_voc(&metadata, &path).expect("Valid objects should be kept");
+
+        assert_eq!(yolo_file.entries.len(), 1);
+        assert_eq!(yolo_file.entries[0].class, 0);
+        assert_eq!(yolo_file.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_yolo_file_from_voc_reports_all_invalid_objects() {
+        let (metadata, path) = create_voc_file(
+            "report_all.xml",
+            &VOC_XML
+                .replace("<name>car", "<name>bus")
+                .replace("<xmax>300</xmax>", "<xmax>900</xmax>"),
+        );
+        let metadata = FileMetadata {
+            row_errors: RowErrorPolicy::ReportAll,
+            ..metadata
+        };
+
+        let error = YoloFile::from_voc(&metadata, &path).expect_err("Expected error");
+
+        assert_eq!(
+            error.to_string(),
+            "File 'tests/sandbox/voc/report_all.xml' has 2 invalid rows"
+        );
+    }
+
+    #[rstest]
+    fn test_voc_export_writes_shapes_as_bounding_boxes(image_data: ImageBuffer<Rgb<u8>, Vec<u8>>) {
+        let image_file =
+            PathBuf::from(format!("{}/voc_export_shapes/shapes.jpg", TEST_SANDBOX_DIR));
+        create_image_file(&image_file, &image_data);
+
+        let pair = ImageLabelPair {
+            name: "shapes".to_string(),
+            image_path: Some(image_file),
+            label_file: Some(YoloFile {
+                stem: "shapes".to_string(),
+                path: "shapes.txt".to_string(),
+                entries: vec![],
+                polygons: vec![YoloPolygon {
+                    class: 0,
+                    points: vec![(0.25, 0.25), (0.75, 0.25), (0.25, 0.75)],
+                }],
+                poses: vec![YoloPose {
+                    entry: YoloEntry {
+                        class: 0,
+                        x_center: 0.5,
+                        y_center: 0.5,
+                        width: 0.5,
+                        height: 0.5,
+                    },
+                    keypoints: vec![YoloKeypoint {
+                        x: 0.5,
+                        y: 0.25,
+                        visibility: Some(2),
+                    }],
+                }],
+                oriented_boxes: vec![YoloOrientedBox {
+                    class: 1,
+                    corners: [(0.5, 0.25), (0.75, 0.5), (0.5, 0.75), (0.25, 0.5)],
+                }],
+                source: LabelSource::Yolo,
+                warnings: vec![],
+                formatting: vec![],
+            }),
+        };
+
+        let class_map = [(0, "person".to_string()), (1, "car".to_string())]
+            .into_iter()
+            .collect();
+
+        let annotation = VocExporter::to_annotation(&pair, &class_map).expect("Unable to convert");
+
+        assert_eq!(
+            annotation
+                .object
+                .iter()
+                .map(|object| (
+                    object.name.as_str(),
+                    object.bndbox.xmin,
+                    object.bndbox.ymin,
+                    object.bndbox.xmax,
+                    object.bndbox.ymax
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("person", 200.0, 200.0, 600.0, 600.0),
+                ("person", 200.0, 200.0, 600.0, 600.0),
+                ("car", 200.0, 200.0, 600.0, 600.0),
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_voc_project_round_trips_through_export(
+        image_data: ImageBuffer<Rgb<u8>, Vec<u8>>,
+        mut create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let this_test_directory = format!("{}/voc_project", TEST_SANDBOX_DIR);
+        let export_directory = format!("{}/voc_export", TEST_SANDBOX_DIR);
+
+        let _ = fs::remove_dir_all(&this_test_directory);
+        let _ = fs::remove_dir_all(&export_directory);
+
+        let image_file = PathBuf::from(format!("{}/street.jpg", this_test_directory));
+        create_image_file(&image_file, &image_data);
+
+        let label_file = PathBuf::from(format!("{}/street.xml", this_test_directory));
+        create_dir_and_write_file(
+            &label_file,
+            &VOC_XML.replace("<height>400</height>", "<height>800</height>"),
+        );
+
+        let orphan_image = PathBuf::from(format!("{}/orphan.jpg", this_test_directory));
+        create_image_file(&orphan_image, &image_data);
+
+        create_yolo_project_config.source_paths.images = this_test_directory.clone();
+        create_yolo_project_config.source_paths.labels = this_test_directory.clone();
+        create_yolo_project_config.export.paths.root = PathBuf::from(&export_directory);
+        create_yolo_project_config.export.split = Split {
+            train: 1.0,
+            validation: 0.0,
+            test: 0.0,
+            ..Default::default()
+        };
+
+        let project =
+            YoloProject::from_voc(&create_yolo_project_config).expect("Unable to load project");
+
+        assert!(project.get_pair("street").is_some());
+        assert!(project
+            .get_invalid_pairs()
+            .iter()
+            .any(|error| matches!(error, PairingError::LabelFileMissing(_))));
+
+        VocExporter::export(project).expect("Unable to export project");
+
+        assert!(PathBuf::from(format!("{}/train/images/street.jpg", export_directory)).exists());
+
+        let mut exported_config = create_yolo_project_config.clone();
+        exported_config.source_paths.images = format!("{}/train/images", export_directory);
+        exported_config.source_paths.labels = format!("{}/train/labels", export_directory);
+
+        let exported =
+            YoloProject::from_voc(&exported_config).expect("Unable to load exported project");
+
+        let entries = exported
+            .get_pair("street")
+            .and_then(|pair| pair.label_file)
+            .expect("Exported pair should be valid")
+            .entries;
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].class, 0);
+        assert_eq!(entries[0].x_center, 0.25);
+        assert_eq!(entries[0].y_center, 0.1875);
+        assert_eq!(entries[1].class, 1);
+        assert_eq!(entries[1].width, 0.5);
+    }
+
+    #[rstest]
+    fn test_voc_copy_export_replaces_links_from_earlier_export(
+        image_data: ImageBuffer<Rgb<u8>, Vec<u8>>,
+        create_yolo_project_config: YoloProjectConfig,
+    ) {
+        let this_test_directory = format!("{}/voc_relink_source", TEST_SANDBOX_DIR);
+        let export_directory = format!("{}/voc_relink", TEST_SANDBOX_DIR);
+        let _ = fs::remove_dir_all(&this_test_directory);
+        let _ = fs::remove_dir_all(&export_directory);
+
+        let image_file = PathBuf::from(format!("{}/box.jpg", this_test_directory));
+        create_image_file(&image_file, &image_data);
+        create_dir_and_write_file(
+            &PathBuf::from(format!("{}/box.txt", this_test_directory)),
+            "0 0.5 0.5 0.5 0.5",
+        );
+        let image_bytes = fs::read(&image_file).expect("Unable to read image");
+
+        let mut config = create_yolo_project_config;
+        config.source_paths.images = this_test_directory.clone();
+        config.source_paths.labels = this_test_directory.clone();
+        config.export.paths.root = PathBuf::from(&export_directory);
+        config.export.overwrite = OverwritePolicy::Merge;
+        config.export.link_mode = LinkMode::AbsoluteSymlink;
+        config.export.split = Split {
+            train: 1.0,
+            validation: 0.0,
+            test: 0.0,
+            ..Default::default()
+        };
+
+        let project = YoloProject::new(&config).expect("Unable to create project");
+        YoloProjectExporter::export(project).expect("Unable to export project");
+
+        let exported_image = PathBuf::from(format!("{}/train/images/box.jpg", export_directory));
+        assert!(fs::symlink_metadata(&exported_image)
+            .expect("Unable to read exported image")
+            .file_type()
+            .is_symlink());
+
+        config.export.link_mode = LinkMode::Copy;
+        let project = YoloProject::new(&config).expect("Unable to create project");
+        VocExporter::export(project).expect("Unable to export project");
+
+        assert!(!fs::symlink_metadata(&exported_image)
+            .expect("Unable to read exported image")
+            .file_type()
+            .is_symlink());
+        assert_eq!(
+            fs::read(&image_file).expect("Unable to read image"),
+            image_bytes
+        );
+        assert_eq!(
+            fs::read(&exported_image).expect("Unable to read exported image"),
+            image_bytes
+        );
+    }
+}
@@ -14,6 +14,7 @@ mod yolo_file_tests {
         let metadata = FileMetadata {
             classes,
             duplicate_tolerance: 0.01,
+            ..Default::default()
         };
         let path = format!("{}/missing.txt", TEST_SANDBOX_DIR);
 